@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::{thread, time};
 
-use crate::graph::Advance;
+use crate::cost::OrderedFloat;
+use crate::graph::{Advance, Retreat};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct State {
@@ -14,6 +15,7 @@ pub struct Props {
 }
 
 impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
     fn advance(&self, edge_props: &Props) -> State {
         // simulating compute time
         thread::sleep(time::Duration::from_millis(10));
@@ -24,7 +26,17 @@ impl Advance<State, Props> for State {
     fn update(&mut self, node_state: State) {
         self.cost = node_state.cost;
     }
-    fn cost(&self) -> Option<f64> {
-        self.cost
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
+
+impl Retreat<State, Props> for State {
+    fn retreat(&self, edge_props: &Props) -> State {
+        // simulating compute time
+        thread::sleep(time::Duration::from_millis(10));
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
     }
 }
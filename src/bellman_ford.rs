@@ -0,0 +1,173 @@
+use rayon::prelude::*;
+use std::collections::VecDeque;
+
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, EdgeId, Graph, NodeId, Topology};
+use crate::search::{Explore, Scratch, SearchResult, Stats};
+
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_search_bellman_ford {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cheapest path to any of the targets like search, but allowing the cost to drop along
+                // the edges (Bellman-Ford with a queue of the nodes whose state has changed, also known as SPFA);
+                // the nodes are taken up again whenever they are reached more cheaply, so all the nodes that can
+                // be reached are explored
+                pub fn search_bellman_ford(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_search_bellman_ford(source, targets) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                // fails with NegativeCycle if the cost keeps dropping along a cycle reachable from the source
+                pub fn try_search_bellman_ford(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    for &id in std::iter::once(&source).chain(targets) {
+                        if !self.contains_node(id) {
+                            return Err(Error::InvalidNode(id));
+                        }
+                    }
+                    let mut scratch = self.relax(&[source])?;
+                    // with the costs allowed to drop, the source is not necessarily the cheapest of the targets
+                    scratch.target = targets
+                        .iter()
+                        .cloned()
+                        .filter(|&id| id == source || scratch.best_incoming[id].is_some())
+                        .min_by_key(|&id| {
+                            self.tentative(&scratch, id)
+                                .and_then(|state| state.cost())
+                                .unwrap_or_else(Cost::zero)
+                        });
+                    self.result(scratch)
+                }
+            }
+        )*
+    };
+}
+
+impl_search_bellman_ford!(Graph, FrozenGraph, MappedGraph);
+
+// the relaxation itself, on anything with the topology of a graph
+pub(crate) trait Relax<NodeState, EdgeProps>: Explore<NodeState, EdgeProps>
+where
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+    // the cheapest states that can be reached from any of the sources, each starting from its state in the graph
+    fn relax(&self, sources: &[NodeId]) -> Result<Scratch<NodeState>, Error> {
+        let mut scratch = Scratch {
+            is_source: vec![false; self.num_nodes()],
+            target: None,
+            best_incoming: vec![None; self.num_nodes()],
+            states: (0..self.num_nodes()).map(|_| None).collect(),
+            stats: Stats::default(),
+        };
+        for &id in sources {
+            scratch.is_source[id] = true;
+        }
+        // the number of edges of the tentative path to each node; with no negative cycle, the cheapest incoming
+        // edges form a forest, so a path with as many edges as there are nodes has to go around a cycle
+        let num_nodes = (0..self.num_nodes())
+            .filter(|&id| self.contains_node(id))
+            .count();
+        let mut lengths = vec![0; self.num_nodes()];
+        let mut is_queued = scratch.is_source.clone();
+        let mut queue = sources.iter().cloned().collect::<VecDeque<_>>();
+        while let Some(from) = queue.pop_front() {
+            is_queued[from] = false;
+            scratch.stats.settled += 1;
+            // the nodes in the queue have all been reached
+            let from_state = self.tentative(&scratch, from).unwrap();
+            let advanced = self
+                .outgoing(from)
+                .collect::<Vec<_>>()
+                .par_iter()
+                .map(|&edge_id| (edge_id, from_state.advance(self.props(edge_id))))
+                .collect::<Vec<_>>();
+            scratch.stats.advanced += advanced.len();
+            for (edge_id, new_state) in advanced {
+                let to = self.edge_to(edge_id);
+                let cost = match new_state.cost() {
+                    None => return Err(Error::NoCostOnAdvancedState(edge_id)),
+                    Some(cost) if cost.is_nan() => return Err(Error::NaNCost(edge_id)),
+                    Some(cost) => cost,
+                };
+                // the sources start with their state in the graph, at no cost if it has none
+                let old_cost = match self.tentative(&scratch, to).and_then(|state| state.cost()) {
+                    None if scratch.is_source[to] => Some(Cost::zero()),
+                    old_cost => old_cost,
+                };
+                if old_cost.is_some_and(|old_cost| old_cost <= cost) {
+                    continue;
+                }
+                scratch.states[to] = Some(new_state);
+                scratch.best_incoming[to] = Some(edge_id);
+                lengths[to] = lengths[from] + 1;
+                if lengths[to] >= num_nodes {
+                    if let Some(cycle) = self.incoming_cycle(&scratch.best_incoming, to) {
+                        return Err(Error::NegativeCycle(cycle));
+                    }
+                }
+                if !is_queued[to] {
+                    queue.push_back(to);
+                    is_queued[to] = true;
+                }
+            }
+        }
+        Ok(scratch)
+    }
+    // the cycle that walking back from the node via the cheapest incoming edges runs into, if any,
+    // starting with its lowest edge id
+    fn incoming_cycle(&self, best_incoming: &[Option<EdgeId>], id: NodeId) -> Option<Vec<EdgeId>> {
+        let mut is_visited = vec![false; self.num_nodes()];
+        let mut node_id = id;
+        while !is_visited[node_id] {
+            is_visited[node_id] = true;
+            node_id = self.edge_from(best_incoming[node_id]?);
+        }
+        let start = node_id;
+        let mut cycle = Vec::new();
+        loop {
+            let edge_id = best_incoming[node_id].unwrap();
+            cycle.push(edge_id);
+            node_id = self.edge_from(edge_id);
+            if node_id == start {
+                break;
+            }
+        }
+        cycle.reverse();
+        let first = (0..cycle.len()).min_by_key(|&index| cycle[index]).unwrap();
+        cycle.rotate_left(first);
+        Some(cycle)
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> Relax<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: Topology<NodeState, EdgeProps> + Sync,
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+}
@@ -0,0 +1,268 @@
+use rayon::prelude::*;
+
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, EdgeId, Graph, NodeId, Retreat, ReverseTopology};
+use crate::priority_queue;
+use crate::search::{checked_cost, SearchResult, Stats};
+
+// one direction of a bidirectional search: forward from the source along the outgoing edges,
+// or backward from the targets along the incoming edges
+struct Side<NodeState, Cost> {
+    is_forward: bool,
+    states: Vec<Option<NodeState>>,
+    // None for the nodes not reached yet
+    costs: Vec<Option<Cost>>,
+    // the cost of the state each path starts from, which doesn't count towards the costs of the side:
+    // zero for the source, whose stored state is where the path starts, but the stored cost for the targets,
+    // since nothing remains to be paid there whatever cost an earlier search left in their states
+    offsets: Vec<Cost>,
+    // the cheapest incoming edge for the forward side, and the cheapest outgoing edge for the backward side
+    best_edge: Vec<Option<EdgeId>>,
+    is_closed: Vec<bool>,
+    queue: priority_queue::Heap<Cost>,
+}
+
+impl<NodeState, Cost: self::Cost> Side<NodeState, Cost> {
+    fn new(is_forward: bool, num_nodes: usize) -> Self {
+        Side {
+            is_forward,
+            states: (0..num_nodes).map(|_| None).collect(),
+            costs: vec![None; num_nodes],
+            offsets: vec![Cost::zero(); num_nodes],
+            best_edge: vec![None; num_nodes],
+            is_closed: vec![false; num_nodes],
+            queue: priority_queue::Heap::new(),
+        }
+    }
+    fn seed(&mut self, id: NodeId, state: NodeState, cost: Cost, offset: Cost) {
+        self.states[id] = Some(state);
+        self.costs[id] = Some(cost.sub(offset));
+        self.offsets[id] = offset;
+        self.queue.insert(id, cost.sub(offset));
+    }
+}
+
+// the cheapest path found so far, going through the node where the two sides meet
+struct Meeting<Cost> {
+    // None until the two sides meet
+    cost: Option<Cost>,
+    node: Option<NodeId>,
+}
+
+// the same on Graph and FrozenGraph, which have the incoming edges as well, like the searches of impl_search
+macro_rules! impl_search_bidirectional {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cheapest path to any of the targets, searching forward from the source and backward
+                // from the targets at the same time until the two searches meet; the backward search starts from
+                // the states stored for the targets, and uses retreat to carry them along the incoming edges,
+                // counting the remaining cost from the cost of the stored state
+                pub fn search_bidirectional(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone + Retreat<NodeState, EdgeProps>,
+                {
+                    match self.try_search_bidirectional(source, targets) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                pub fn try_search_bidirectional(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone + Retreat<NodeState, EdgeProps>,
+                {
+                    // leave the trivial and the invalid searches to the plain search
+                    if targets.contains(&source) || targets.iter().any(|&id| !self.contains_node(id)) {
+                        return self.try_search(source, targets);
+                    }
+                    if !self.contains_node(source) {
+                        return Err(Error::InvalidNode(source));
+                    }
+                    self.search_both_ways(source, targets)
+                }
+            }
+        )*
+    };
+}
+
+impl_search_bidirectional!(Graph, FrozenGraph);
+
+// the search itself, on anything with the topology of a graph and its incoming edges
+trait SearchBothWays<NodeState, EdgeProps>:
+    ReverseTopology<NodeState, EdgeProps> + Sync + Sized
+where
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+    fn search_both_ways(
+        &self,
+        source: NodeId,
+        targets: &[NodeId],
+    ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+    where
+        NodeState: Clone + Retreat<NodeState, EdgeProps>,
+    {
+        let mut stats = Stats::default();
+        let mut forward = Side::new(true, self.num_nodes());
+        let mut backward = Side::new(false, self.num_nodes());
+        let state = self.state(source);
+        let cost = state.cost().unwrap_or_else(Cost::zero);
+        forward.seed(source, state.clone(), cost, Cost::zero());
+        for &target in targets {
+            let state = self.state(target);
+            let cost = state.cost().unwrap_or_else(Cost::zero);
+            backward.seed(target, state.clone(), cost, cost);
+        }
+        let mut meeting = Meeting {
+            cost: None,
+            node: None,
+        };
+        // no path through the nodes that are still in the queues can be cheaper than the sum of their cheapest
+        // items, since each side settles its nodes in the order of their cost
+        while let (Some((_, forward_cost)), Some((_, backward_cost))) =
+            (forward.queue.peek(), backward.queue.peek())
+        {
+            if meeting
+                .cost
+                .is_some_and(|cost| forward_cost.add(backward_cost) >= cost)
+            {
+                break;
+            }
+            if forward_cost <= backward_cost {
+                self.expand(&mut forward, &backward, &mut meeting, &mut stats)?;
+            } else {
+                self.expand(&mut backward, &forward, &mut meeting, &mut stats)?;
+            }
+        }
+        let node = meeting.node.ok_or(Error::NoPath)?;
+        // join the forward path to the meeting node with the backward path from the meeting node
+        let mut edges = Vec::new();
+        let mut node_id = node;
+        while let Some(edge_id) = forward.best_edge[node_id] {
+            edges.push(edge_id);
+            node_id = self.edge_from(edge_id);
+        }
+        edges.reverse();
+        let mut state = forward.states[node].clone().unwrap();
+        let mut node_id = node;
+        while let Some(edge_id) = backward.best_edge[node_id] {
+            // the backward states are not the forward states, so the forward state is advanced to the target
+            state = state.advance(self.props(edge_id));
+            checked_cost(&state, edge_id)?;
+            stats.advanced += 1;
+            edges.push(edge_id);
+            node_id = self.edge_to(edge_id);
+        }
+        let nodes = std::iter::once(source)
+            .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+            .collect();
+        Ok(SearchResult {
+            source,
+            target: node_id,
+            nodes,
+            edges,
+            cost: state.cost().unwrap_or_else(Cost::zero),
+            state,
+            states: forward.states,
+            stats,
+        })
+    }
+    // settle the cheapest node of one side, and advance (or retreat) its state along its edges
+    fn expand(
+        &self,
+        side: &mut Side<NodeState, NodeState::Cost>,
+        other: &Side<NodeState, NodeState::Cost>,
+        meeting: &mut Meeting<NodeState::Cost>,
+        stats: &mut Stats,
+    ) -> Result<(), Error>
+    where
+        NodeState: Retreat<NodeState, EdgeProps>,
+    {
+        let (from, _) = side.queue.extract_min().unwrap();
+        if side.is_closed[from] {
+            stats.stale += 1;
+            return Ok(());
+        }
+        side.is_closed[from] = true;
+        stats.settled += 1;
+        let is_forward = side.is_forward;
+        let neighbour = |edge_id| {
+            if is_forward {
+                self.edge_to(edge_id)
+            } else {
+                self.edge_from(edge_id)
+            }
+        };
+        let is_open = |edge_id: &EdgeId| {
+            let to = neighbour(*edge_id);
+            to != from && !side.is_closed[to]
+        };
+        let edge_ids = if is_forward {
+            self.outgoing(from).filter(is_open).collect::<Vec<_>>()
+        } else {
+            self.incoming(from).filter(is_open).collect::<Vec<_>>()
+        };
+        let from_state = side.states[from].as_ref().unwrap();
+        stats.advanced += edge_ids.len();
+        for (edge_id, new_state) in edge_ids
+            .par_iter()
+            .map(|&edge_id| {
+                let props = self.props(edge_id);
+                let new_state = if is_forward {
+                    from_state.advance(props)
+                } else {
+                    from_state.retreat(props)
+                };
+                (edge_id, new_state)
+            })
+            .collect::<Vec<_>>()
+        {
+            let to = neighbour(edge_id);
+            let offset = side.offsets[from];
+            let cost = checked_cost(&new_state, edge_id)?;
+            if cost < offset {
+                return Err(Error::CostDecrease(edge_id));
+            }
+            let cost = cost.sub(offset);
+            if side.costs[to].is_some_and(|old_cost| old_cost <= cost) {
+                continue;
+            }
+            side.states[to] = Some(new_state);
+            side.costs[to] = Some(cost);
+            side.offsets[to] = offset;
+            side.best_edge[to] = Some(edge_id);
+            side.queue.insert(to, cost);
+            if let Some(other_cost) = other.costs[to] {
+                let cost = cost.add(other_cost);
+                if meeting.cost.is_none_or(|meeting_cost| cost < meeting_cost) {
+                    meeting.cost = Some(cost);
+                    meeting.node = Some(to);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> SearchBothWays<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: ReverseTopology<NodeState, EdgeProps> + Sync,
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+}
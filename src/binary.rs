@@ -0,0 +1,410 @@
+use memmap2::Mmap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::{TryFrom, TryInto};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::graph::{Data, Edge, EdgeId, Graph, Node, NodeId, Topology};
+
+// the binary format starts with a header:
+//   magic "DIJKSTRA", the version as a 32-bit word and 4 reserved bytes,
+//   then the number of nodes, the number of edges (including the removed ones), the number of outgoing edge ids,
+//   and the length of the data section, as 64-bit words;
+// followed by the sections, each padded to a multiple of 8 bytes:
+//   offsets: for each node and one past the last, where its outgoing edge ids start in the next section
+//   outgoing: the outgoing edge ids of all the nodes, one node after the other (compressed sparse row)
+//   sources, targets: the node at the start and at the end of each edge
+//   removed nodes, removed edges: one byte for each, 1 if removed
+//   data: the node states and edge props serialised with bincode
+// all the numbers are little-endian, so that the arrays can be used in place on 64-bit little-endian machines
+const MAGIC: &[u8; 8] = b"DIJKSTRA";
+pub const VERSION: u32 = 1;
+const HEADER_LEN: usize = 48;
+
+// where the sections are, in bytes from the start of the header
+struct Layout {
+    num_nodes: usize,
+    num_edges: usize,
+    offsets: Range<usize>,
+    outgoing: Range<usize>,
+    sources: Range<usize>,
+    targets: Range<usize>,
+    removed_nodes: Range<usize>,
+    removed_edges: Range<usize>,
+    data: Range<usize>,
+}
+
+impl Layout {
+    // None if the sections would take more bytes than can be addressed
+    fn new(
+        num_nodes: usize,
+        num_edges: usize,
+        num_outgoing: usize,
+        data_len: usize,
+    ) -> Option<Layout> {
+        let mut end = HEADER_LEN;
+        let mut section = |len: Option<usize>| {
+            let start = end;
+            let len = len?;
+            end = start.checked_add(len.checked_add(7)? / 8 * 8)?;
+            Some(start..start + len)
+        };
+        Some(Layout {
+            num_nodes,
+            num_edges,
+            offsets: section(num_nodes.checked_add(1)?.checked_mul(8))?,
+            outgoing: section(num_outgoing.checked_mul(8))?,
+            sources: section(num_edges.checked_mul(8))?,
+            targets: section(num_edges.checked_mul(8))?,
+            removed_nodes: section(Some(num_nodes))?,
+            removed_edges: section(Some(num_edges))?,
+            data: section(Some(data_len))?,
+        })
+    }
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&[0; 4]);
+        for len in [
+            self.num_nodes,
+            self.num_edges,
+            self.outgoing.len() / 8,
+            self.data.len(),
+        ] {
+            header.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+        header
+    }
+    // check the header at the start of the bytes, and that the bytes are long enough for all the sections
+    fn parse(bytes: &[u8]) -> Result<Layout, Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(Error::InvalidFormat("not a binary graph".to_string()));
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(Error::InvalidFormat(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let len = |index: usize| {
+            let start = 16 + 8 * index;
+            let len = u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+            usize::try_from(len).ok()
+        };
+        let layout = match (len(0), len(1), len(2), len(3)) {
+            (Some(num_nodes), Some(num_edges), Some(num_outgoing), Some(data_len)) => {
+                Layout::new(num_nodes, num_edges, num_outgoing, data_len)
+            }
+            _ => None,
+        };
+        match layout {
+            Some(layout) if layout.data.end <= bytes.len() => Ok(layout),
+            _ => Err(Error::InvalidFormat("truncated".to_string())),
+        }
+    }
+}
+
+impl<NodeState, EdgeProps> Graph<NodeState, EdgeProps> {
+    // write the graph in the binary format, keeping the ids of the nodes and the edges, removed ones included
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> Result<(), Error>
+    where
+        NodeState: Serialize,
+        EdgeProps: Serialize,
+    {
+        let data = bincode::serialize(&(&self.states, &self.props))
+            .map_err(|error| Error::InvalidFormat(error.to_string()))?;
+        let mut offsets = Vec::with_capacity(self.nodes.len() + 1);
+        let mut outgoing = Vec::new();
+        offsets.push(0);
+        for node in &self.nodes {
+            outgoing.extend_from_slice(&node.outgoing);
+            offsets.push(outgoing.len());
+        }
+        let layout = Layout::new(
+            self.nodes.len(),
+            self.edges.len(),
+            outgoing.len(),
+            data.len(),
+        )
+        .ok_or_else(|| Error::InvalidFormat("graph too large".to_string()))?;
+        let sources = self.edges.iter().map(|edge| edge.from).collect::<Vec<_>>();
+        let targets = self.edges.iter().map(|edge| edge.to).collect::<Vec<_>>();
+        let removed_nodes = self
+            .nodes
+            .iter()
+            .map(|node| node.removed as u8)
+            .collect::<Vec<_>>();
+        let removed_edges = self
+            .edges
+            .iter()
+            .map(|edge| edge.removed as u8)
+            .collect::<Vec<_>>();
+        writer.write_all(&layout.header())?;
+        for words in [&offsets, &outgoing, &sources, &targets] {
+            let bytes = words
+                .iter()
+                .flat_map(|&word| (word as u64).to_le_bytes())
+                .collect::<Vec<_>>();
+            writer.write_all(&bytes)?;
+        }
+        for bytes in [&removed_nodes, &removed_edges, &data] {
+            writer.write_all(bytes)?;
+            writer.write_all(&[0; 7][..(8 - bytes.len() % 8) % 8])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+    // read a graph written by write_binary, checking that its ids are consistent like on deserialisation
+    pub fn read_binary<R: Read>(mut reader: R) -> Result<Self, Error>
+    where
+        NodeState: DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let layout = Layout::parse(&bytes)?;
+        let words = |range: &Range<usize>| {
+            bytes[range.clone()]
+                .chunks_exact(8)
+                .map(|word| u64::from_le_bytes(word.try_into().unwrap()) as usize)
+                .collect::<Vec<_>>()
+        };
+        let offsets = words(&layout.offsets);
+        let outgoing = words(&layout.outgoing);
+        let sources = words(&layout.sources);
+        let targets = words(&layout.targets);
+        check_offsets(&offsets, &outgoing)?;
+        let (states, props) = read_data(&bytes, &layout)?;
+        let nodes = (0..layout.num_nodes)
+            .map(|id| Node {
+                id,
+                outgoing: outgoing[offsets[id]..offsets[id + 1]].to_vec(),
+                removed: bytes[layout.removed_nodes.start + id] != 0,
+            })
+            .collect();
+        let edges = (0..layout.num_edges)
+            .map(|id| Edge {
+                id,
+                from: sources[id],
+                to: targets[id],
+                removed: bytes[layout.removed_edges.start + id] != 0,
+            })
+            .collect();
+        Graph::try_from(Data {
+            nodes,
+            edges,
+            states,
+            props,
+        })
+    }
+}
+
+// the offsets have to be in order and within the outgoing edge ids, which read_binary splits up by them;
+// the ids themselves are checked on building the graph
+fn check_offsets(offsets: &[usize], outgoing: &[EdgeId]) -> Result<(), Error> {
+    if offsets[0] != 0
+        || offsets.windows(2).any(|pair| pair[0] > pair[1])
+        || offsets[offsets.len() - 1] != outgoing.len()
+    {
+        return Err(Error::InvalidFormat("offsets out of order".to_string()));
+    }
+    Ok(())
+}
+
+// the words of a section of a mapped file, in place
+fn words<'a>(map: &'a [u8], range: &Range<usize>) -> &'a [usize] {
+    let bytes = &map[range.clone()];
+    // any 8 bytes are a valid usize, and the section is aligned, as checked on opening
+    unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const usize, bytes.len() / 8) }
+}
+
+// the ids in the topology of a mapped file have to be within bounds and agree with each other,
+// like the ones that Data::check checks for read_binary, which are located the same way
+fn check_topology(map: &[u8], layout: &Layout) -> Result<(), Error> {
+    let offsets = words(map, &layout.offsets);
+    let outgoing = words(map, &layout.outgoing);
+    let sources = words(map, &layout.sources);
+    let targets = words(map, &layout.targets);
+    check_offsets(offsets, outgoing)?;
+    for (field, ids) in [("from", sources), ("to", targets)] {
+        if let Some((index, id)) = ids
+            .iter()
+            .enumerate()
+            .find(|&(_, &id)| id >= layout.num_nodes)
+        {
+            return Err(Error::InvalidFormat(format!(
+                "edges[{}].{}: invalid node id {}",
+                index, field, id
+            )));
+        }
+    }
+    for node_id in 0..layout.num_nodes {
+        for (index, &edge_id) in outgoing[offsets[node_id]..offsets[node_id + 1]]
+            .iter()
+            .enumerate()
+        {
+            let message = if edge_id >= layout.num_edges {
+                format!("invalid edge id {}", edge_id)
+            } else if sources[edge_id] != node_id {
+                format!("edge {} is from node {}", edge_id, sources[edge_id])
+            } else {
+                continue;
+            };
+            return Err(Error::InvalidFormat(format!(
+                "nodes[{}].outgoing[{}]: {}",
+                node_id, index, message
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn read_data<NodeState, EdgeProps>(
+    bytes: &[u8],
+    layout: &Layout,
+) -> Result<(Vec<NodeState>, Vec<EdgeProps>), Error>
+where
+    NodeState: DeserializeOwned,
+    EdgeProps: DeserializeOwned,
+{
+    let (states, props): (Vec<NodeState>, Vec<EdgeProps>) =
+        bincode::deserialize(&bytes[layout.data.clone()])
+            .map_err(|error| Error::InvalidFormat(format!("data section: {}", error)))?;
+    if states.len() != layout.num_nodes || props.len() != layout.num_edges {
+        return Err(Error::InvalidFormat(
+            "data section doesn't match the numbers of nodes and edges".to_string(),
+        ));
+    }
+    Ok((states, props))
+}
+
+// graph opened from a file in the binary format by mapping it into memory: the arrays of the topology are used
+// in place, without reading the file up front, and only the node states and edge props are deserialised;
+// the searches run on it the same as on FrozenGraph, but for the ones that go backward from the targets,
+// since the file doesn't store the incoming edges
+pub struct MappedGraph<NodeState, EdgeProps> {
+    map: Mmap,
+    layout: Layout,
+    states: Vec<NodeState>,
+    props: Vec<EdgeProps>,
+}
+
+impl<NodeState, EdgeProps> MappedGraph<NodeState, EdgeProps> {
+    // only the header and the data section are checked, so that opening takes no longer than deserialising
+    // the states and props; a file with ids out of bounds makes the searches panic rather than fail,
+    // so use open_checked for the files that might be corrupt; the file must not be modified while it is open
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error>
+    where
+        NodeState: DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        MappedGraph::map(path, false)
+    }
+    // the same as open, but also checking all the ids of the topology once, which takes time linear in the number
+    // of edges, so that the searches can index it without bounds errors
+    pub fn open_checked<P: AsRef<Path>>(path: P) -> Result<Self, Error>
+    where
+        NodeState: DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        MappedGraph::map(path, true)
+    }
+    fn map<P: AsRef<Path>>(path: P, is_checked: bool) -> Result<Self, Error>
+    where
+        NodeState: DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        if !cfg!(all(target_endian = "little", target_pointer_width = "64")) {
+            return Err(Error::InvalidFormat(
+                "mapping needs a 64-bit little-endian machine".to_string(),
+            ));
+        }
+        let file = File::open(path)?;
+        // safe as long as the file isn't modified while mapped, as above
+        let map = unsafe { Mmap::map(&file)? };
+        let layout = Layout::parse(&map)?;
+        // the sections start at multiples of 8 bytes, and the map itself starts at a page boundary
+        assert_eq!(map.as_ptr() as usize % std::mem::align_of::<usize>(), 0);
+        if is_checked {
+            check_topology(&map, &layout)?;
+        }
+        let (states, props) = read_data(&map, &layout)?;
+        Ok(MappedGraph {
+            map,
+            layout,
+            states,
+            props,
+        })
+    }
+    fn words(&self, range: &Range<usize>) -> &[usize] {
+        words(&self.map, range)
+    }
+    pub fn outgoing(&self, id: NodeId) -> &[EdgeId] {
+        let offsets = self.words(&self.layout.offsets);
+        &self.words(&self.layout.outgoing)[offsets[id]..offsets[id + 1]]
+    }
+    pub fn edge_from(&self, id: EdgeId) -> NodeId {
+        self.words(&self.layout.sources)[id]
+    }
+    pub fn edge_to(&self, id: EdgeId) -> NodeId {
+        self.words(&self.layout.targets)[id]
+    }
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        id < self.layout.num_nodes && self.map[self.layout.removed_nodes.start + id] == 0
+    }
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        id < self.layout.num_edges && self.map[self.layout.removed_edges.start + id] == 0
+    }
+    // ids of the nodes that have not been removed
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.layout.num_nodes).filter(move |&id| self.contains_node(id))
+    }
+    // ids of the edges that have not been removed
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        (0..self.layout.num_edges).filter(move |&id| self.contains_edge(id))
+    }
+    // number of node ids, including the ids of the removed nodes
+    pub fn num_nodes(&self) -> usize {
+        self.layout.num_nodes
+    }
+    // number of edge ids, including the ids of the removed edges
+    pub fn num_edges(&self) -> usize {
+        self.layout.num_edges
+    }
+    pub fn state(&self, id: NodeId) -> &NodeState {
+        &self.states[id]
+    }
+    pub fn props(&self, id: EdgeId) -> &EdgeProps {
+        &self.props[id]
+    }
+}
+
+impl<NodeState, EdgeProps> Topology<NodeState, EdgeProps> for MappedGraph<NodeState, EdgeProps> {
+    fn num_nodes(&self) -> usize {
+        self.num_nodes()
+    }
+    fn contains_node(&self, id: NodeId) -> bool {
+        self.contains_node(id)
+    }
+    fn outgoing(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.outgoing(id).iter().cloned()
+    }
+    fn edge_from(&self, id: EdgeId) -> NodeId {
+        self.edge_from(id)
+    }
+    fn edge_to(&self, id: EdgeId) -> NodeId {
+        self.edge_to(id)
+    }
+    fn state(&self, id: NodeId) -> &NodeState {
+        self.state(id)
+    }
+    fn props(&self, id: EdgeId) -> &EdgeProps {
+        self.props(id)
+    }
+}
@@ -0,0 +1,161 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use crate::error::Error;
+use crate::graph::{Data, Edge, EdgeId, Graph, Node, NodeId};
+
+// graph in a compact JSON schema, without the ids that only repeat the positions in the arrays:
+// the node states, the start and end node of each edge, and the edge props, all in the order of the ids;
+// the outgoing edge ids of the nodes are rebuilt from the edges, in the order of the edge ids
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactGraph<NodeState, EdgeProps> {
+    pub states: Vec<NodeState>,
+    pub edges: Vec<(NodeId, NodeId)>,
+    pub props: Vec<EdgeProps>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_nodes: Vec<NodeId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_edges: Vec<EdgeId>,
+}
+
+impl<NodeState, EdgeProps> CompactGraph<NodeState, EdgeProps> {
+    // the ids have to be in range, and the edges that are not removed can't start or end at removed nodes;
+    // the error tells where the first mismatch is
+    fn check(&self) -> Result<(), Error> {
+        let invalid = |path: String, message: String| {
+            Err(Error::InvalidFormat(format!("{}: {}", path, message)))
+        };
+        let num_nodes = self.states.len();
+        if self.props.len() != self.edges.len() {
+            return invalid(
+                "props".to_string(),
+                format!("{} props for {} edges", self.props.len(), self.edges.len()),
+            );
+        }
+        // the paths are only formatted for an error, like in Data::check
+        let mut is_removed_node = vec![false; num_nodes];
+        for (index, &id) in self.removed_nodes.iter().enumerate() {
+            let path = || format!("removed_nodes[{}]", index);
+            match is_removed_node.get(id) {
+                None => return invalid(path(), format!("invalid node id {}", id)),
+                Some(true) => return invalid(path(), format!("node {} is listed twice", id)),
+                Some(false) => is_removed_node[id] = true,
+            }
+        }
+        let mut is_removed_edge = vec![false; self.edges.len()];
+        for (index, &id) in self.removed_edges.iter().enumerate() {
+            let path = || format!("removed_edges[{}]", index);
+            match is_removed_edge.get(id) {
+                None => return invalid(path(), format!("invalid edge id {}", id)),
+                Some(true) => return invalid(path(), format!("edge {} is listed twice", id)),
+                Some(false) => is_removed_edge[id] = true,
+            }
+        }
+        for (index, &(from, to)) in self.edges.iter().enumerate() {
+            for (field, id) in [(0, from), (1, to)] {
+                let path = || format!("edges[{}][{}]", index, field);
+                if id >= num_nodes {
+                    return invalid(path(), format!("invalid node id {}", id));
+                }
+                if !is_removed_edge[index] && is_removed_node[id] {
+                    return invalid(path(), format!("node {} is removed", id));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<NodeState, EdgeProps> TryFrom<CompactGraph<NodeState, EdgeProps>>
+    for Graph<NodeState, EdgeProps>
+{
+    type Error = Error;
+    fn try_from(compact: CompactGraph<NodeState, EdgeProps>) -> Result<Self, Error> {
+        compact.check()?;
+        let mut nodes = (0..compact.states.len())
+            .map(|id| Node {
+                id,
+                outgoing: Vec::new(),
+                removed: false,
+            })
+            .collect::<Vec<_>>();
+        let mut edges = compact
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(id, &(from, to))| Edge {
+                id,
+                from,
+                to,
+                removed: false,
+            })
+            .collect::<Vec<_>>();
+        for &id in &compact.removed_nodes {
+            nodes[id].removed = true;
+        }
+        for &id in &compact.removed_edges {
+            edges[id].removed = true;
+        }
+        for edge in edges.iter().filter(|edge| !edge.removed) {
+            nodes[edge.from].outgoing.push(edge.id);
+        }
+        Graph::try_from(Data {
+            nodes,
+            edges,
+            states: compact.states,
+            props: compact.props,
+        })
+    }
+}
+
+impl<NodeState, EdgeProps> Graph<NodeState, EdgeProps> {
+    // the graph in the compact schema, keeping the ids of the nodes and the edges, removed ones included,
+    // and borrowing the states and props; the outgoing edge ids of each node come back in the order of the ids
+    pub fn to_compact(&self) -> CompactGraph<&NodeState, &EdgeProps> {
+        CompactGraph {
+            states: self.states.iter().collect(),
+            edges: self.edges.iter().map(|edge| (edge.from, edge.to)).collect(),
+            props: self.props.iter().collect(),
+            removed_nodes: self
+                .nodes
+                .iter()
+                .filter(|node| node.removed)
+                .map(|node| node.id)
+                .collect(),
+            removed_edges: self
+                .edges
+                .iter()
+                .filter(|edge| edge.removed)
+                .map(|edge| edge.id)
+                .collect(),
+        }
+    }
+    pub fn write_compact_json<W: Write>(&self, writer: W) -> Result<(), Error>
+    where
+        NodeState: Serialize,
+        EdgeProps: Serialize,
+    {
+        serde_json::to_writer(writer, &self.to_compact()).map_err(json_error)
+    }
+    // read a graph in the compact schema, checking that its ids are consistent
+    pub fn read_compact_json<R: Read>(reader: R) -> Result<Self, Error>
+    where
+        NodeState: DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        let compact: CompactGraph<NodeState, EdgeProps> =
+            serde_json::from_reader(reader).map_err(json_error)?;
+        Graph::try_from(compact)
+    }
+}
+
+// a JSON syntax or type error, which serde_json locates by line and column
+fn json_error(error: serde_json::Error) -> Error {
+    if error.is_io() {
+        Error::Io(error.to_string())
+    } else {
+        Error::InvalidFormat(error.to_string())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::binary::MappedGraph;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Graph, NodeId, TryAdvance};
+use crate::pareto::ExploreLabels;
+use crate::search::SearchResult;
+
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_constrained_path {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + TryAdvance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cheapest path from the source to the target that keeps within the limits on the
+                // resources, without modifying the node states of the graph; the edges that try_advance returns
+                // None for are not taken, and each node keeps all the states it is reached with that are not
+                // dominated by another one, since a more expensive state with more resources left might be the
+                // only one to get to the target
+                pub fn constrained_path(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_constrained_path(source, target) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to find constrained path: {}", error),
+                    }
+                }
+                pub fn try_constrained_path(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let labels = self.explore_labels(source, target, true, |state, props| {
+                        state.try_advance(props)
+                    })?;
+                    let &label_id = labels.front.first().ok_or(Error::NoPath)?;
+                    Ok(self.labelled_path(&labels, label_id))
+                }
+            }
+        )*
+    };
+}
+
+impl_constrained_path!(Graph, FrozenGraph, MappedGraph);
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::priority_queue::Key;
+
+// the cost of a path, which has to be totally ordered so that the cheapest path is well defined
+pub trait Cost: Copy + Ord + Send + Sync + fmt::Debug {
+    // the cost of the empty path
+    fn zero() -> Self;
+    // the cost of two consecutive parts of a path, such as the cost so far and the estimate of A*
+    fn add(self, other: Self) -> Self;
+    // the inverse of add, such as the difference between the potentials of Johnson's all-pairs search
+    fn sub(self, other: Self) -> Self;
+    // lower than the other cost by more than rounding errors, which only the float wrapper has;
+    // used to check the consistency of A* heuristics, which floats can miss by rounding alone
+    fn is_clearly_below(&self, other: &Self) -> bool {
+        self < other
+    }
+    // only the float wrapper can hold a cost that is not a number
+    fn is_nan(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(
+            impl Cost for $t {
+                fn zero() -> Self {
+                    0
+                }
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+                fn sub(self, other: Self) -> Self {
+                    self - other
+                }
+            }
+        )*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// f64 that is totally ordered, so that it can be used as a cost;
+// NaN is ordered above all the numbers, but the searches reject it
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OrderedFloat(pub f64);
+
+impl PartialEq for OrderedFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrderedFloat {}
+
+impl PartialOrd for OrderedFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            // -0.0 and 0.0 are equal
+            (false, false) => self.0.partial_cmp(&other.0).unwrap(),
+        }
+    }
+}
+
+// so that costs can be compared with plain numbers
+impl PartialEq<f64> for OrderedFloat {
+    fn eq(&self, other: &f64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<f64> for OrderedFloat {
+    fn from(value: f64) -> Self {
+        OrderedFloat(value)
+    }
+}
+
+impl From<OrderedFloat> for f64 {
+    fn from(value: OrderedFloat) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for OrderedFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Cost for OrderedFloat {
+    fn zero() -> Self {
+        OrderedFloat(0.0)
+    }
+    fn add(self, other: Self) -> Self {
+        OrderedFloat(self.0 + other.0)
+    }
+    fn sub(self, other: Self) -> Self {
+        OrderedFloat(self.0 - other.0)
+    }
+    fn is_clearly_below(&self, other: &Self) -> bool {
+        // relative to the magnitude of the costs, so that large costs get as much slack as small ones
+        self.0 < other.0 - 1e-9 * self.0.abs().max(other.0.abs()).max(1.0)
+    }
+    fn is_nan(&self) -> bool {
+        self.0.is_nan()
+    }
+}
+
+impl Key for OrderedFloat {
+    fn key(&self) -> u64 {
+        // -0.0 has the sign bit set, so it is mapped to the key of 0.0
+        (self.0 + 0.0).key()
+    }
+}
+
+// tuples are compared lexicographically, such as (hops, distance) to find the cheapest of the paths with fewest hops
+macro_rules! impl_cost_tuple {
+    ($(($($name:ident $index:tt),+)),*) => {
+        $(
+            impl<$($name: Cost),+> Cost for ($($name,)+) {
+                fn zero() -> Self {
+                    ($($name::zero(),)+)
+                }
+                fn add(self, other: Self) -> Self {
+                    ($(self.$index.add(other.$index),)+)
+                }
+                fn sub(self, other: Self) -> Self {
+                    ($(self.$index.sub(other.$index),)+)
+                }
+                fn is_clearly_below(&self, other: &Self) -> bool {
+                    // the first element that differs by more than rounding errors decides
+                    $(
+                        if self.$index.is_clearly_below(&other.$index) {
+                            return true;
+                        }
+                        if other.$index.is_clearly_below(&self.$index) {
+                            return false;
+                        }
+                    )+
+                    false
+                }
+                fn is_nan(&self) -> bool {
+                    false $(|| self.$index.is_nan())+
+                }
+            }
+        )*
+    };
+}
+
+impl_cost_tuple!((A 0), (A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
@@ -0,0 +1,687 @@
+use serde::de::value::{Error as DeError, MapDeserializer, StrDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible};
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use crate::compact::CompactGraph;
+use crate::error::Error;
+use crate::graph::{Advance, Graph, NodeId};
+use crate::search::SearchResult;
+
+// edge lists with a header line naming the columns: "from" and "to" for the ids of the nodes an edge connects,
+// and any other columns for the fields of the edge props, which are deserialised from them with serde;
+// node tables likewise, with an "id" column and columns for the fields of the node states;
+// the fields are separated by the delimiter, and quoted with double quotes if they contain it
+pub const CSV: char = ',';
+pub const TSV: char = '\t';
+
+impl<NodeState, EdgeProps> Graph<NodeState, EdgeProps> {
+    // read an edge list, with as many nodes as its highest node id calls for, all in their default state
+    pub fn read_csv<R: Read>(edges: R, delimiter: char) -> Result<Self, Error>
+    where
+        NodeState: Default + DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        Graph::read_csv_tables(edges, None::<&[u8]>, delimiter)
+    }
+    // read an edge list and a node table with the states of the nodes, which don't have to be all there
+    pub fn read_csv_with_nodes<E: Read, N: Read>(
+        edges: E,
+        nodes: N,
+        delimiter: char,
+    ) -> Result<Self, Error>
+    where
+        NodeState: Default + DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        Graph::read_csv_tables(edges, Some(nodes), delimiter)
+    }
+    fn read_csv_tables<E: Read, N: Read>(
+        edges: E,
+        nodes: Option<N>,
+        delimiter: char,
+    ) -> Result<Self, Error>
+    where
+        NodeState: Default + DeserializeOwned,
+        EdgeProps: DeserializeOwned,
+    {
+        let mut compact = CompactGraph {
+            states: Vec::new(),
+            edges: Vec::new(),
+            props: Vec::new(),
+            removed_nodes: Vec::new(),
+            removed_edges: Vec::new(),
+        };
+        let mut num_nodes = 0;
+        let table = Table::read(edges, delimiter, &["from", "to"])?;
+        for (line, fields) in &table.rows {
+            let from = table.node_id(*line, fields, 0)?;
+            let to = table.node_id(*line, fields, 1)?;
+            num_nodes = num_nodes.max(from + 1).max(to + 1);
+            compact.edges.push((from, to));
+            compact.props.push(table.deserialize(*line, fields)?);
+        }
+        let mut states = Vec::new();
+        if let Some(nodes) = nodes {
+            let table = Table::read(nodes, delimiter, &["id"])?;
+            for (line, fields) in &table.rows {
+                let id = table.node_id(*line, fields, 0)?;
+                num_nodes = num_nodes.max(id + 1);
+                states.push((id, *line, table.deserialize(*line, fields)?));
+            }
+        }
+        compact.states = (0..num_nodes).map(|_| NodeState::default()).collect();
+        let mut is_listed = vec![false; num_nodes];
+        for (id, line, state) in states {
+            if is_listed[id] {
+                return Err(Error::InvalidFormat(format!(
+                    "line {}: node {} is listed twice",
+                    line, id
+                )));
+            }
+            is_listed[id] = true;
+            compact.states[id] = state;
+        }
+        Graph::try_from(compact)
+    }
+    // write the edges that have not been removed, in the order of their ids,
+    // which are the ids they get back on reading if none have been removed
+    pub fn write_csv_edges<W: Write>(&self, writer: W, delimiter: char) -> Result<(), Error>
+    where
+        EdgeProps: Serialize,
+    {
+        let rows = self
+            .edges
+            .iter()
+            .filter(|edge| !edge.removed)
+            .map(|edge| {
+                let ids = vec![edge.from.to_string(), edge.to.to_string()];
+                Ok((ids, columns(&self.props[edge.id])?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_table(writer, delimiter, &["from", "to"], rows)
+    }
+    // write the states of the nodes that have not been removed
+    pub fn write_csv_nodes<W: Write>(&self, writer: W, delimiter: char) -> Result<(), Error>
+    where
+        NodeState: Serialize,
+    {
+        let rows = self
+            .nodes
+            .iter()
+            .filter(|node| !node.removed)
+            .map(|node| Ok((vec![node.id.to_string()], columns(&self.states[node.id])?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_table(writer, delimiter, &["id"], rows)
+    }
+    // write the nodes along the path of a search result, each with the edge it is reached by
+    // (empty for the source) and the state the search reached it with
+    pub fn write_csv_path<W: Write>(
+        &self,
+        result: &SearchResult<NodeState, NodeState::Cost>,
+        writer: W,
+        delimiter: char,
+    ) -> Result<(), Error>
+    where
+        NodeState: Serialize + Advance<NodeState, EdgeProps>,
+    {
+        let edges = std::iter::once(None).chain(result.edges.iter().map(Some));
+        let rows = result
+            .nodes
+            .iter()
+            .zip(edges)
+            .map(|(&id, edge_id)| {
+                let state = result.states[id].as_ref().unwrap_or(&self.states[id]);
+                let ids = vec![
+                    id.to_string(),
+                    edge_id
+                        .map(|edge_id| edge_id.to_string())
+                        .unwrap_or_default(),
+                ];
+                Ok((ids, columns(state)?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_table(writer, delimiter, &["node", "edge"], rows)
+    }
+}
+
+// a table read with its header, keeping the line each row starts on for the error messages
+struct Table {
+    // the positions of the id columns, in the order they were asked for
+    ids: Vec<usize>,
+    // the names of the other columns, with their positions
+    columns: Vec<(usize, String)>,
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+impl Table {
+    fn read<R: Read>(mut reader: R, delimiter: char, id_columns: &[&str]) -> Result<Table, Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut records = records(&text, delimiter)?.into_iter();
+        let header = match records.next() {
+            Some((_, header)) => header,
+            None => return Err(Error::InvalidFormat("no header".to_string())),
+        };
+        let ids = id_columns
+            .iter()
+            .map(|&name| {
+                header
+                    .iter()
+                    .position(|column| column == name)
+                    .ok_or_else(|| Error::InvalidFormat(format!("line 1: no {} column", name)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let columns: Vec<_> = header
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !ids.contains(index))
+            .collect();
+        let rows = records.collect::<Vec<_>>();
+        for (line, fields) in &rows {
+            if fields.len() != ids.len() + columns.len() {
+                return Err(Error::InvalidFormat(format!(
+                    "line {}: {} fields for {} columns",
+                    line,
+                    fields.len(),
+                    ids.len() + columns.len()
+                )));
+            }
+        }
+        Ok(Table { ids, columns, rows })
+    }
+    fn node_id(&self, line: usize, fields: &[String], id: usize) -> Result<NodeId, Error> {
+        let field = &fields[self.ids[id]];
+        field
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidFormat(format!("line {}: invalid node id {}", line, field)))
+    }
+    // the value of the other columns, by their names
+    fn deserialize<T: DeserializeOwned>(&self, line: usize, fields: &[String]) -> Result<T, Error> {
+        let map = self
+            .columns
+            .iter()
+            .map(|(index, name)| (name.as_str(), Cell(&fields[*index])));
+        T::deserialize(MapDeserializer::<_, DeError>::new(map))
+            .map_err(|error| Error::InvalidFormat(format!("line {}: {}", line, error)))
+    }
+}
+
+// split the text into records of fields, each with the number of the line it starts on, skipping empty lines;
+// a field may be quoted, so that it can contain the delimiter, line breaks, and quotes, which are doubled
+fn records(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => is_quoted = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+        } else if c == '"' && field.is_empty() {
+            is_quoted = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            if record.len() > 1 || !record[0].is_empty() {
+                records.push((start, std::mem::take(&mut record)));
+            }
+            record.clear();
+            line += 1;
+            start = line;
+        } else {
+            field.push(c);
+        }
+    }
+    if is_quoted {
+        return Err(Error::InvalidFormat(format!(
+            "line {}: unterminated quote",
+            start
+        )));
+    }
+    if !record.is_empty() || !field.is_empty() {
+        record.push(field);
+        records.push((start, record));
+    }
+    Ok(records)
+}
+
+// the fields of a value serialised as a struct (or a map), by their names, in the order they are serialised;
+// a field can only hold a single value, since the cells are read back as such
+fn columns<T: Serialize>(value: &T) -> Result<Vec<(String, String)>, Error> {
+    value
+        .serialize(Columns)
+        .map_err(|error| Error::InvalidFormat(error.to_string()))
+}
+
+fn not_a_struct<T>() -> Result<T, DeError> {
+    Err(ser::Error::custom("not a struct"))
+}
+
+fn nested<T>() -> Result<T, DeError> {
+    Err(ser::Error::custom("nested values don't fit in a field"))
+}
+
+// serialises a struct or a map, or a newtype around one, into its fields
+struct Columns;
+
+// the fields serialised so far, and the key of the map entry whose value comes next
+struct Fields {
+    fields: Vec<(String, String)>,
+    key: Option<String>,
+}
+
+// serialises a single value into a field, the way Cell reads it back: None as an empty field,
+// and the unit variants of an enum by their names
+struct Field;
+
+macro_rules! serialize_scalars {
+    ($($method:ident: $type:ty),* => |$value:ident| $body:expr) => {
+        $(
+            fn $method(self, $value: $type) -> Result<Self::Ok, DeError> {
+                $body
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for Columns {
+    type Ok = Vec<(String, String)>;
+    type Error = DeError;
+    type SerializeSeq = Impossible<Self::Ok, DeError>;
+    type SerializeTuple = Impossible<Self::Ok, DeError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, DeError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, DeError>;
+    type SerializeMap = Fields;
+    type SerializeStruct = Fields;
+    type SerializeStructVariant = Impossible<Self::Ok, DeError>;
+    serialize_scalars!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+        serialize_unit_struct: &'static str
+        => |_value| not_a_struct()
+    );
+    fn serialize_none(self) -> Result<Self::Ok, DeError> {
+        not_a_struct()
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Self::Ok, DeError> {
+        not_a_struct()
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, DeError> {
+        not_a_struct()
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, DeError> {
+        not_a_struct()
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, DeError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, DeError> {
+        not_a_struct()
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, DeError> {
+        not_a_struct()
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, DeError> {
+        not_a_struct()
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, DeError> {
+        not_a_struct()
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, DeError> {
+        not_a_struct()
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Fields, DeError> {
+        Ok(Fields {
+            fields: Vec::new(),
+            key: None,
+        })
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Fields, DeError> {
+        Ok(Fields {
+            fields: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, DeError> {
+        not_a_struct()
+    }
+}
+
+impl Fields {
+    fn push<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> Result<(), DeError> {
+        let field = value
+            .serialize(Field)
+            .map_err(|error| ser::Error::custom(format!("{}: {}", name, error)))?;
+        self.fields.push((name, field));
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Fields {
+    type Ok = Vec<(String, String)>;
+    type Error = DeError;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), DeError> {
+        self.push(name.to_string(), value)
+    }
+    fn end(self) -> Result<Self::Ok, DeError> {
+        Ok(self.fields)
+    }
+}
+
+impl ser::SerializeMap for Fields {
+    type Ok = Vec<(String, String)>;
+    type Error = DeError;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), DeError> {
+        self.key = Some(key.serialize(Field)?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), DeError> {
+        let name = self.key.take().unwrap_or_default();
+        self.push(name, value)
+    }
+    fn end(self) -> Result<Self::Ok, DeError> {
+        Ok(self.fields)
+    }
+}
+
+impl ser::Serializer for Field {
+    type Ok = String;
+    type Error = DeError;
+    type SerializeSeq = Impossible<String, DeError>;
+    type SerializeTuple = Impossible<String, DeError>;
+    type SerializeTupleStruct = Impossible<String, DeError>;
+    type SerializeTupleVariant = Impossible<String, DeError>;
+    type SerializeMap = Impossible<String, DeError>;
+    type SerializeStruct = Impossible<String, DeError>;
+    type SerializeStructVariant = Impossible<String, DeError>;
+    serialize_scalars!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str
+        => |value| Ok(value.to_string())
+    );
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, DeError> {
+        nested()
+    }
+    fn serialize_none(self) -> Result<String, DeError> {
+        Ok(String::new())
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, DeError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<String, DeError> {
+        Ok(String::new())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, DeError> {
+        Ok(String::new())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, DeError> {
+        Ok(variant.to_string())
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, DeError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, DeError> {
+        nested()
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, DeError> {
+        nested()
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, DeError> {
+        nested()
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, DeError> {
+        nested()
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, DeError> {
+        nested()
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, DeError> {
+        nested()
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, DeError> {
+        nested()
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, DeError> {
+        nested()
+    }
+}
+
+// the ids of a row, and the fields of its value by their names
+type Row = (Vec<String>, Vec<(String, String)>);
+
+// write the header and the rows, each with its ids and the fields of its value; the columns for the values
+// are named after the fields of the first one, and a field that another value doesn't have is left empty
+fn write_table<W: Write>(
+    mut writer: W,
+    delimiter: char,
+    id_columns: &[&str],
+    rows: Vec<Row>,
+) -> Result<(), Error> {
+    let names: Vec<String> = rows
+        .first()
+        .map(|(_, fields)| fields.iter().map(|(name, _)| name.clone()).collect())
+        .unwrap_or_default();
+    let header = id_columns.iter().map(|&name| name.to_string());
+    write_record(&mut writer, delimiter, header.chain(names.iter().cloned()))?;
+    for (ids, fields) in rows {
+        let fields = names.iter().map(|name| {
+            fields
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|(_, field)| field.clone())
+                .unwrap_or_default()
+        });
+        write_record(&mut writer, delimiter, ids.into_iter().chain(fields))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_record<W: Write>(
+    writer: &mut W,
+    delimiter: char,
+    fields: impl Iterator<Item = String>,
+) -> Result<(), Error> {
+    let fields = fields
+        .map(|field| {
+            if field.contains(&[delimiter, '"', '\n', '\r'][..]) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>();
+    writeln!(writer, "{}", fields.join(&delimiter.to_string()))?;
+    Ok(())
+}
+
+// a single field of a record, parsed as whatever type the field of the struct has,
+// with an empty field for None
+struct Cell<'a>(&'a str);
+
+impl<'a> IntoDeserializer<'_, DeError> for Cell<'a> {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                match self.0.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(error) => Err(de::Error::custom(format!("{}: {}", self.0, error))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Cell<'a> {
+    type Error = DeError;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_str(self.0)
+    }
+    deserialize_parsed!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64
+    );
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_enum(StrDeserializer::<DeError>::new(self.0))
+    }
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
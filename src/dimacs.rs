@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+
+use crate::compact::CompactGraph;
+use crate::error::Error;
+use crate::graph::{Graph, NodeId};
+
+// the format of the shortest path challenge of DIMACS: a graph file with a problem line "p sp <nodes> <arcs>"
+// followed by a line "a <from> <to> <weight>" for each edge, and a coordinate file with a problem line
+// "p aux sp co <nodes>" followed by a line "v <node> <x> <y>" for each node; lines starting with "c" are comments,
+// and the node ids start from 1, so that node 1 in the files is node 0 in the graph
+
+impl<NodeState, EdgeProps> Graph<NodeState, EdgeProps> {
+    // read a graph file, making the state of each node from its id and the props of each edge from its weight
+    pub fn read_dimacs<R, NewState, NewProps>(
+        reader: R,
+        new_state: NewState,
+        new_props: NewProps,
+    ) -> Result<Self, Error>
+    where
+        R: BufRead,
+        NewState: Fn(NodeId) -> NodeState,
+        NewProps: Fn(i64) -> EdgeProps,
+    {
+        let mut compact = CompactGraph {
+            states: Vec::new(),
+            edges: Vec::new(),
+            props: Vec::new(),
+            removed_nodes: Vec::new(),
+            removed_edges: Vec::new(),
+        };
+        let mut num_arcs: Option<usize> = None;
+        for_each_line(reader, |number, fields| match (fields, num_arcs) {
+            (["p", "sp", nodes, arcs], None) => {
+                let num_nodes = parse(number, nodes)?;
+                num_arcs = Some(parse(number, arcs)?);
+                compact.states = (0..num_nodes).map(&new_state).collect();
+                Ok(())
+            }
+            (["a", from, to, weight], Some(_)) => {
+                let from = node_id(number, from, compact.states.len())?;
+                let to = node_id(number, to, compact.states.len())?;
+                compact.edges.push((from, to));
+                compact.props.push(new_props(parse(number, weight)?));
+                Ok(())
+            }
+            _ => Err(invalid_line(number, fields)),
+        })?;
+        match num_arcs {
+            None => Err(Error::InvalidFormat("no problem line".to_string())),
+            Some(num_arcs) if num_arcs != compact.edges.len() => {
+                Err(Error::InvalidFormat(format!(
+                    "{} arcs for {} in the problem line",
+                    compact.edges.len(),
+                    num_arcs
+                )))
+            }
+            Some(_) => Graph::try_from(compact),
+        }
+    }
+    // write a graph file, with the weight of each edge given by its props; the removed nodes are written
+    // as nodes without edges, so that the ids of the others stay the same
+    pub fn write_dimacs<W, Weight>(&self, mut writer: W, weight: Weight) -> Result<(), Error>
+    where
+        W: Write,
+        Weight: Fn(&EdgeProps) -> i64,
+    {
+        let edges = self.edges.iter().filter(|edge| !edge.removed);
+        writeln!(
+            writer,
+            "p sp {} {}",
+            self.nodes.len(),
+            edges.clone().count()
+        )?;
+        for edge in edges {
+            let weight = weight(&self.props[edge.id]);
+            writeln!(writer, "a {} {} {}", edge.from + 1, edge.to + 1, weight)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// read a coordinate file, giving the coordinates of each node, such as for the heuristic of an A* search
+pub fn read_dimacs_coordinates<R: BufRead>(reader: R) -> Result<Vec<(i64, i64)>, Error> {
+    let mut coordinates: Option<Vec<Option<(i64, i64)>>> = None;
+    for_each_line(reader, |number, fields| match (fields, &mut coordinates) {
+        (["p", "aux", "sp", "co", nodes], None) => {
+            coordinates = Some(vec![None; parse(number, nodes)?]);
+            Ok(())
+        }
+        (["v", id, x, y], Some(coordinates)) => {
+            let id = node_id(number, id, coordinates.len())?;
+            if coordinates[id].is_some() {
+                return Err(Error::InvalidFormat(format!(
+                    "line {}: node {} is listed twice",
+                    number,
+                    id + 1
+                )));
+            }
+            coordinates[id] = Some((parse(number, x)?, parse(number, y)?));
+            Ok(())
+        }
+        _ => Err(invalid_line(number, fields)),
+    })?;
+    let coordinates =
+        coordinates.ok_or_else(|| Error::InvalidFormat("no problem line".to_string()))?;
+    coordinates
+        .iter()
+        .enumerate()
+        .map(|(id, point)| {
+            point.ok_or_else(|| Error::InvalidFormat(format!("no coordinates for node {}", id + 1)))
+        })
+        .collect()
+}
+
+pub fn write_dimacs_coordinates<W: Write>(
+    mut writer: W,
+    coordinates: &[(i64, i64)],
+) -> Result<(), Error> {
+    writeln!(writer, "p aux sp co {}", coordinates.len())?;
+    for (id, (x, y)) in coordinates.iter().enumerate() {
+        writeln!(writer, "v {} {} {}", id + 1, x, y)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// call back with the number and the fields of each line, skipping the comments and the empty lines
+fn for_each_line<R, Callback>(reader: R, mut callback: Callback) -> Result<(), Error>
+where
+    R: BufRead,
+    Callback: FnMut(usize, &[&str]) -> Result<(), Error>,
+{
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.is_empty() || fields[0] == "c" {
+            continue;
+        }
+        callback(index + 1, &fields)?;
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(number: usize, field: &str) -> Result<T, Error> {
+    field
+        .parse()
+        .map_err(|_| Error::InvalidFormat(format!("line {}: invalid number {}", number, field)))
+}
+
+fn node_id(number: usize, field: &str, num_nodes: usize) -> Result<NodeId, Error> {
+    match parse::<NodeId>(number, field)? {
+        id if id >= 1 && id <= num_nodes => Ok(id - 1),
+        _ => Err(Error::InvalidFormat(format!(
+            "line {}: invalid node id {}",
+            number, field
+        ))),
+    }
+}
+
+fn invalid_line(number: usize, fields: &[&str]) -> Error {
+    Error::InvalidFormat(format!("line {}: unexpected {}", number, fields.join(" ")))
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::graph::{EdgeId, NodeId};
+use crate::search::Frontier;
+
+// reasons for a graph operation or a search to fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    // the node id is not in the graph
+    InvalidNode(NodeId),
+    // the edge id is not in the graph
+    InvalidEdge(EdgeId),
+    // the state advanced along the edge has no cost
+    NoCostOnAdvancedState(EdgeId),
+    // the state advanced along the edge has a cost below zero
+    NegativeCost(EdgeId),
+    // the state advanced along the edge has a cost that is not a number
+    NaNCost(EdgeId),
+    // the state advanced along the edge has a lower cost than the state it has been advanced from
+    CostDecrease(EdgeId),
+    // the edges of a cycle along which the cost keeps dropping, so that there is no cheapest path
+    NegativeCycle(Vec<EdgeId>),
+    // the heuristic drops by more than the cost of the edge
+    InconsistentHeuristic(EdgeId),
+    // departing later along the edge can arrive earlier, so that a search might miss the earliest arrival
+    NotFifo(EdgeId),
+    // none of the targets can be reached from the source
+    NoPath,
+    // the search has reached one of the limits of its budget before reaching any of the targets
+    BudgetExhausted(Box<Frontier>),
+    // reading or writing a graph failed, with the message of the I/O error
+    Io(String),
+    // the data doesn't hold a graph in the expected format, with what is wrong with it
+    InvalidFormat(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidNode(id) => write!(f, "invalid node id {}", id),
+            Error::InvalidEdge(id) => write!(f, "invalid edge id {}", id),
+            Error::NoCostOnAdvancedState(id) => {
+                write!(f, "no cost on the state advanced along edge {}", id)
+            }
+            Error::NegativeCost(id) => {
+                write!(f, "negative cost on the state advanced along edge {}", id)
+            }
+            Error::NaNCost(id) => write!(f, "NaN cost on the state advanced along edge {}", id),
+            Error::CostDecrease(id) => {
+                write!(f, "cost decreases when advancing along edge {}", id)
+            }
+            Error::NegativeCycle(edge_ids) => {
+                write!(f, "negative cycle along edges {:?}", edge_ids)
+            }
+            Error::InconsistentHeuristic(id) => {
+                write!(f, "heuristic is inconsistent along edge {}", id)
+            }
+            Error::NotFifo(id) => write!(f, "edge {} is not FIFO", id),
+            Error::NoPath => write!(f, "no path from the source to any of the targets"),
+            Error::BudgetExhausted(frontier) => {
+                write!(
+                    f,
+                    "search budget exhausted: {:?} limit reached",
+                    frontier.limit
+                )
+            }
+            Error::Io(message) => write!(f, "I/O error: {}", message),
+            Error::InvalidFormat(message) => write!(f, "invalid format: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
@@ -0,0 +1,103 @@
+use std::ops::Range;
+
+use crate::graph::{EdgeId, NodeId, ReverseTopology, Topology};
+
+// immutable graph in compressed sparse row form, made by Graph::freeze: the outgoing edges of each node
+// have consecutive ids, so that their targets and props are next to each other in memory instead of
+// scattered over a vector of edge ids per node; all the searches run on it the same as on Graph,
+// including the ones that go backward from the targets, for which it keeps the incoming edges as well
+#[derive(Debug, Clone)]
+pub struct FrozenGraph<NodeState, EdgeProps> {
+    // the outgoing edges of each node are the edge ids from its offset up to the offset of the next node
+    pub(crate) offsets: Vec<EdgeId>,
+    // the node at the end of each edge
+    pub(crate) targets: Vec<NodeId>,
+    // the same for the incoming edges: the ids of the edges that end at each node are the ones
+    // in incoming from its offset up to the offset of the next node
+    pub(crate) incoming_offsets: Vec<usize>,
+    pub(crate) incoming: Vec<EdgeId>,
+    pub(crate) states: Vec<NodeState>,
+    pub(crate) props: Vec<EdgeProps>,
+    pub(crate) removed: Vec<bool>,
+    // the id of each edge in the graph it was frozen from
+    pub(crate) original_edges: Vec<EdgeId>,
+}
+
+impl<NodeState, EdgeProps> FrozenGraph<NodeState, EdgeProps> {
+    pub fn outgoing(&self, id: NodeId) -> Range<EdgeId> {
+        self.offsets[id]..self.offsets[id + 1]
+    }
+    pub fn incoming(&self, id: NodeId) -> &[EdgeId] {
+        &self.incoming[self.incoming_offsets[id]..self.incoming_offsets[id + 1]]
+    }
+    // the node at the start of the edge, found among the offsets, since only the ends of the edges are stored
+    pub fn edge_from(&self, id: EdgeId) -> NodeId {
+        self.offsets.partition_point(|&offset| offset <= id) - 1
+    }
+    pub fn edge_to(&self, id: EdgeId) -> NodeId {
+        self.targets[id]
+    }
+    // the id the edge had before the graph was frozen
+    pub fn original_edge(&self, id: EdgeId) -> EdgeId {
+        self.original_edges[id]
+    }
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        id < self.removed.len() && !self.removed[id]
+    }
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        id < self.targets.len()
+    }
+    // ids of the nodes that were not removed
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.removed.len()).filter(move |&id| !self.removed[id])
+    }
+    pub fn edge_ids(&self) -> Range<EdgeId> {
+        0..self.targets.len()
+    }
+    // number of node ids, including the ids of the removed nodes
+    pub fn num_nodes(&self) -> usize {
+        self.removed.len()
+    }
+    // removed edges are left out, so this is also the number of edges
+    pub fn num_edges(&self) -> usize {
+        self.targets.len()
+    }
+    pub fn state(&self, id: NodeId) -> &NodeState {
+        &self.states[id]
+    }
+    pub fn props(&self, id: EdgeId) -> &EdgeProps {
+        &self.props[id]
+    }
+}
+
+impl<NodeState, EdgeProps> Topology<NodeState, EdgeProps> for FrozenGraph<NodeState, EdgeProps> {
+    fn num_nodes(&self) -> usize {
+        self.num_nodes()
+    }
+    fn contains_node(&self, id: NodeId) -> bool {
+        self.contains_node(id)
+    }
+    fn outgoing(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.outgoing(id)
+    }
+    fn edge_from(&self, id: EdgeId) -> NodeId {
+        self.edge_from(id)
+    }
+    fn edge_to(&self, id: EdgeId) -> NodeId {
+        self.edge_to(id)
+    }
+    fn state(&self, id: NodeId) -> &NodeState {
+        self.state(id)
+    }
+    fn props(&self, id: EdgeId) -> &EdgeProps {
+        self.props(id)
+    }
+}
+
+impl<NodeState, EdgeProps> ReverseTopology<NodeState, EdgeProps>
+    for FrozenGraph<NodeState, EdgeProps>
+{
+    fn incoming(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.incoming(id).iter().cloned()
+    }
+}
@@ -1,22 +1,150 @@
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::ops::Not;
 
-use crate::priority_queue;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::search::{Explore, Query};
 
 // data-oriented graph with user-defined node states and edge props;
-// nodes and edges can be inserted but not deleted
+// removed nodes and edges are only marked as such, so that the ids of the others stay valid until compacted
 #[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(try_from = "Data<NodeState, EdgeProps>")]
 pub struct Graph<NodeState, EdgeProps> {
-    nodes: Vec<Node>,
-    edges: Vec<Edge>,
-    states: Vec<NodeState>,
-    props: Vec<EdgeProps>,
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) edges: Vec<Edge>,
+    pub(crate) states: Vec<NodeState>,
+    pub(crate) props: Vec<EdgeProps>,
+    // incoming edge ids for each node, the reverse of the outgoing edge ids of the nodes
+    #[serde(skip)]
+    incoming: Vec<Vec<EdgeId>>,
+}
+
+// serialised graph without the incoming edge ids, which are rebuilt from the edges on deserialisation,
+// after checking that the rest is consistent
+#[derive(Deserialize)]
+pub(crate) struct Data<NodeState, EdgeProps> {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    pub states: Vec<NodeState>,
+    pub props: Vec<EdgeProps>,
+}
+
+impl<NodeState, EdgeProps> Data<NodeState, EdgeProps> {
+    // the ids have to match the positions, and the outgoing edge ids of each node have to be exactly
+    // the edges that start from it and have not been removed; the error tells where the first mismatch is
+    fn check(&self) -> Result<(), Error> {
+        let invalid = |path: String, message: String| {
+            Err(Error::InvalidFormat(format!("{}: {}", path, message)))
+        };
+        if self.states.len() != self.nodes.len() {
+            return invalid(
+                "states".to_string(),
+                format!(
+                    "{} states for {} nodes",
+                    self.states.len(),
+                    self.nodes.len()
+                ),
+            );
+        }
+        if self.props.len() != self.edges.len() {
+            return invalid(
+                "props".to_string(),
+                format!("{} props for {} edges", self.props.len(), self.edges.len()),
+            );
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.id != index {
+                return invalid(
+                    format!("nodes[{}].id", index),
+                    format!("expected {}", index),
+                );
+            }
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            if edge.id != index {
+                return invalid(
+                    format!("edges[{}].id", index),
+                    format!("expected {}", index),
+                );
+            }
+            for (field, id) in [("from", edge.from), ("to", edge.to)] {
+                if id >= self.nodes.len() {
+                    return invalid(
+                        format!("edges[{}].{}", index, field),
+                        format!("invalid node id {}", id),
+                    );
+                }
+                if !edge.removed && self.nodes[id].removed {
+                    return invalid(
+                        format!("edges[{}].{}", index, field),
+                        format!("node {} is removed", id),
+                    );
+                }
+            }
+        }
+        let mut is_listed = vec![false; self.edges.len()];
+        for node in &self.nodes {
+            for (index, &edge_id) in node.outgoing.iter().enumerate() {
+                // only formatted for an error, since this runs for every edge of every graph read
+                let path = || format!("nodes[{}].outgoing[{}]", node.id, index);
+                match self.edges.get(edge_id) {
+                    None => return invalid(path(), format!("invalid edge id {}", edge_id)),
+                    Some(edge) if edge.removed => {
+                        return invalid(path(), format!("edge {} is removed", edge_id))
+                    }
+                    Some(edge) if edge.from != node.id => {
+                        return invalid(
+                            path(),
+                            format!("edge {} is from node {}", edge_id, edge.from),
+                        )
+                    }
+                    Some(_) if is_listed[edge_id] => {
+                        return invalid(path(), format!("edge {} is listed twice", edge_id))
+                    }
+                    Some(_) => is_listed[edge_id] = true,
+                }
+            }
+        }
+        if let Some(edge) = self
+            .edges
+            .iter()
+            .find(|edge| !edge.removed && !is_listed[edge.id])
+        {
+            return invalid(
+                format!("nodes[{}].outgoing", edge.from),
+                format!("edge {} is missing", edge.id),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<NodeState, EdgeProps> TryFrom<Data<NodeState, EdgeProps>> for Graph<NodeState, EdgeProps> {
+    type Error = Error;
+    fn try_from(data: Data<NodeState, EdgeProps>) -> Result<Self, Error> {
+        data.check()?;
+        let mut incoming = vec![Vec::new(); data.nodes.len()];
+        for edge in data.edges.iter().filter(|edge| !edge.removed) {
+            incoming[edge.to].push(edge.id);
+        }
+        Ok(Graph {
+            nodes: data.nodes,
+            edges: data.edges,
+            states: data.states,
+            props: data.props,
+            incoming,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Node {
     pub id: NodeId,
     pub outgoing: Vec<EdgeId>,
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub removed: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -24,16 +152,97 @@ pub struct Edge {
     pub id: EdgeId,
     pub from: NodeId,
     pub to: NodeId,
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub removed: bool,
+}
+
+// new ids of the nodes and edges after compacting the graph, None for the removed ones
+#[derive(Debug, Clone, PartialEq)]
+pub struct Remap {
+    pub nodes: Vec<Option<NodeId>>,
+    pub edges: Vec<Option<EdgeId>>,
 }
 
 pub type NodeId = usize;
 pub type EdgeId = usize;
 
-// NodeState has to implement this trait
+// NodeState has to implement this trait; the searches settle each node once, with its cheapest state, so
+// advancing a cheaper state along an edge must never give a more expensive state than advancing a costlier one
+// (first in, first out, when the cost is a time and the edges take longer at some times than at others)
 pub trait Advance<NodeState, EdgeProps> {
+    // such as an integer, OrderedFloat, or a tuple of those
+    type Cost: Cost;
     fn advance(&self, edge_props: &EdgeProps) -> NodeState;
     fn update(&mut self, node_state: NodeState);
-    fn cost(&self) -> Option<f64>;
+    fn cost(&self) -> Option<Self::Cost>;
+}
+
+// NodeState has to implement this trait for the searches that go backward from the targets,
+// where the cost of a state is the cost remaining to reach a target
+pub trait Retreat<NodeState, EdgeProps> {
+    // retreat a given state at the end of an edge to the start of the edge
+    fn retreat(&self, edge_props: &EdgeProps) -> NodeState;
+}
+
+// NodeState has to implement this trait for the multi-criteria searches, where no single cost
+// tells which of two states is better; a state has to cost no more than the states it dominates
+pub trait Dominate<NodeState, EdgeProps>: Advance<NodeState, EdgeProps> {
+    // whether this state is at least as good as the other one in every criterion
+    fn dominates(&self, other: &NodeState) -> bool;
+}
+
+// NodeState has to implement this trait for the resource-constrained searches, where the states carry
+// resources with limits on them, and a state dominates another one only if it has no less of each resource left
+pub trait TryAdvance<NodeState, EdgeProps>: Dominate<NodeState, EdgeProps> {
+    // advance a given state along an edge, or None if that would break any of the limits
+    fn try_advance(&self, edge_props: &EdgeProps) -> Option<NodeState>;
+}
+
+// what the searches need to know about a graph, whichever way it stores its edges
+pub trait Topology<NodeState, EdgeProps> {
+    // number of node ids, including the ids of the removed nodes
+    fn num_nodes(&self) -> usize;
+    fn contains_node(&self, id: NodeId) -> bool;
+    fn outgoing(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_;
+    fn edge_from(&self, id: EdgeId) -> NodeId;
+    fn edge_to(&self, id: EdgeId) -> NodeId;
+    fn state(&self, id: NodeId) -> &NodeState;
+    fn props(&self, id: EdgeId) -> &EdgeProps;
+}
+
+// the incoming edges as well, for the searches that go backward from the targets
+pub trait ReverseTopology<NodeState, EdgeProps>: Topology<NodeState, EdgeProps> {
+    fn incoming(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_;
+}
+
+impl<NodeState, EdgeProps> Topology<NodeState, EdgeProps> for Graph<NodeState, EdgeProps> {
+    fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    fn contains_node(&self, id: NodeId) -> bool {
+        id < self.nodes.len() && !self.nodes[id].removed
+    }
+    fn outgoing(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.nodes[id].outgoing.iter().cloned()
+    }
+    fn edge_from(&self, id: EdgeId) -> NodeId {
+        self.edges[id].from
+    }
+    fn edge_to(&self, id: EdgeId) -> NodeId {
+        self.edges[id].to
+    }
+    fn state(&self, id: NodeId) -> &NodeState {
+        &self.states[id]
+    }
+    fn props(&self, id: EdgeId) -> &EdgeProps {
+        &self.props[id]
+    }
+}
+
+impl<NodeState, EdgeProps> ReverseTopology<NodeState, EdgeProps> for Graph<NodeState, EdgeProps> {
+    fn incoming(&self, id: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        self.incoming[id].iter().cloned()
+    }
 }
 
 impl<NodeState, EdgeProps> Graph<NodeState, EdgeProps>
@@ -47,6 +256,7 @@ where
             edges: Vec::new(),
             states: Vec::new(),
             props: Vec::new(),
+            incoming: Vec::new(),
         }
     }
     pub fn insert_node(&mut self, state: NodeState) -> NodeId {
@@ -54,91 +264,236 @@ where
         self.nodes.push(Node {
             id: new_node_id,
             outgoing: Vec::new(),
+            removed: false,
         });
         self.states.push(state);
+        self.incoming.push(Vec::new());
         new_node_id
     }
     pub fn insert_edge(&mut self, from: NodeId, to: NodeId, props: EdgeProps) -> EdgeId {
+        match self.try_insert_edge(from, to, props) {
+            Ok(edge_id) => edge_id,
+            Err(error) => panic!("failed to insert edge: {}", error),
+        }
+    }
+    pub fn try_insert_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        props: EdgeProps,
+    ) -> Result<EdgeId, Error> {
+        for &id in &[from, to] {
+            if !self.contains_node(id) {
+                return Err(Error::InvalidNode(id));
+            }
+        }
         let new_edge_id = self.edges.len();
         self.edges.push(Edge {
             id: new_edge_id,
             from,
             to,
+            removed: false,
         });
         self.props.push(props);
         self.nodes[from].outgoing.push(new_edge_id);
-        new_edge_id
+        self.incoming[to].push(new_edge_id);
+        Ok(new_edge_id)
     }
-    // find the cheapest path to any of the targets
-    pub fn best_path(&mut self, source: NodeId, targets: &[NodeId]) -> Option<Vec<EdgeId>> {
-        if targets.contains(&source) {
-            return Some(Vec::new());
-        }
-        // from the source, use breadth-first search to find the cheapest incoming edge for each node
-        let mut best_incoming = vec![None; self.nodes.len()];
-        let mut best_target = None;
-        let mut is_closed = vec![false; self.nodes.len()];
-        let mut queue = priority_queue::Heap::<f64>::new();
-        queue.insert(source, self.states[source].cost().unwrap_or(0.0));
-        while !queue.is_empty() {
-            let (from, _) = queue.extract_min().unwrap();
-            if targets.contains(&from) {
-                // all other targets are going to be more expensive, since we're using priority queue
-                best_target = Some(from);
-                break;
-            }
-            is_closed[from] = true;
-            let outgoing_edge_ids = self.nodes[from]
-                .outgoing
-                .iter()
-                .cloned()
-                .filter(|&edge_id| {
-                    let to = self.edges[edge_id].to;
-                    to != from && !is_closed[to]
-                })
-                .collect::<Vec<_>>();
-            for (edge_id, state) in outgoing_edge_ids
-                .par_iter()
-                .map(|&edge_id| (edge_id, self.states[from].advance(&self.props[edge_id])))
-                .collect::<Vec<_>>()
-            {
-                let to = self.edges[edge_id].to;
-                let cost = state.cost().unwrap();
-                if let Some(old_cost) = self.states[to].cost() {
-                    if old_cost <= cost {
-                        continue;
-                    }
-                }
-                self.states[to].update(state);
-                best_incoming[to] = Some(edge_id);
-                queue.insert(to, cost);
-                // the queue might still have the old more expensive items for 'to',
-                // but they will be discarded when they eventually get to the front of the queue
+    // remove the edge from the outgoing and incoming edges of its nodes, keeping its id reserved
+    pub fn remove_edge(&mut self, id: EdgeId) {
+        if let Err(error) = self.try_remove_edge(id) {
+            panic!("failed to remove edge: {}", error);
+        }
+    }
+    pub fn try_remove_edge(&mut self, id: EdgeId) -> Result<(), Error> {
+        if !self.contains_edge(id) {
+            return Err(Error::InvalidEdge(id));
+        }
+        let Edge { from, to, .. } = self.edges[id];
+        self.nodes[from].outgoing.retain(|&edge_id| edge_id != id);
+        self.incoming[to].retain(|&edge_id| edge_id != id);
+        self.edges[id].removed = true;
+        Ok(())
+    }
+    // remove the node along with all its outgoing and incoming edges, keeping its id reserved
+    pub fn remove_node(&mut self, id: NodeId) {
+        if let Err(error) = self.try_remove_node(id) {
+            panic!("failed to remove node: {}", error);
+        }
+    }
+    pub fn try_remove_node(&mut self, id: NodeId) -> Result<(), Error> {
+        if !self.contains_node(id) {
+            return Err(Error::InvalidNode(id));
+        }
+        let mut edge_ids = self.nodes[id].outgoing.clone();
+        edge_ids.extend(self.incoming[id].iter().cloned());
+        edge_ids.sort_unstable();
+        edge_ids.dedup();
+        for edge_id in edge_ids {
+            self.try_remove_edge(edge_id)?;
+        }
+        self.nodes[id].removed = true;
+        Ok(())
+    }
+    // drop the removed nodes and edges, renumbering the others in the same order
+    pub fn compact(&mut self) -> Remap {
+        let mut remap = Remap {
+            nodes: vec![None; self.nodes.len()],
+            edges: vec![None; self.edges.len()],
+        };
+        for (new_id, id) in self.node_ids().enumerate().collect::<Vec<_>>() {
+            remap.nodes[id] = Some(new_id);
+        }
+        for (new_id, id) in self.edge_ids().enumerate().collect::<Vec<_>>() {
+            remap.edges[id] = Some(new_id);
+        }
+        let keep_nodes = self
+            .nodes
+            .iter()
+            .map(|node| !node.removed)
+            .collect::<Vec<_>>();
+        let keep_edges = self
+            .edges
+            .iter()
+            .map(|edge| !edge.removed)
+            .collect::<Vec<_>>();
+        let node_id = |id: NodeId| remap.nodes[id].unwrap();
+        let edge_id = |id: EdgeId| remap.edges[id].unwrap();
+        self.nodes = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .filter(|node| !node.removed)
+            .map(|node| Node {
+                id: node_id(node.id),
+                outgoing: node.outgoing.into_iter().map(edge_id).collect(),
+                removed: false,
+            })
+            .collect();
+        self.incoming = std::mem::take(&mut self.incoming)
+            .into_iter()
+            .zip(&keep_nodes)
+            .filter(|(_, &keep)| keep)
+            .map(|(incoming, _)| incoming.into_iter().map(edge_id).collect())
+            .collect();
+        self.edges = std::mem::take(&mut self.edges)
+            .into_iter()
+            .filter(|edge| !edge.removed)
+            .map(|edge| Edge {
+                id: edge_id(edge.id),
+                from: node_id(edge.from),
+                to: node_id(edge.to),
+                removed: false,
+            })
+            .collect();
+        self.states = std::mem::take(&mut self.states)
+            .into_iter()
+            .zip(keep_nodes)
+            .filter(|(_, keep)| *keep)
+            .map(|(state, _)| state)
+            .collect();
+        self.props = std::mem::take(&mut self.props)
+            .into_iter()
+            .zip(keep_edges)
+            .filter(|(_, keep)| *keep)
+            .map(|(props, _)| props)
+            .collect();
+        remap
+    }
+    // turn the graph into compressed sparse row form for faster searches, once it isn't going to change anymore;
+    // the edges are renumbered in the order of the nodes they start from, leaving out the removed ones,
+    // and the node ids stay the same
+    pub fn freeze(self) -> FrozenGraph<NodeState, EdgeProps> {
+        let mut props = self.props.into_iter().map(Some).collect::<Vec<_>>();
+        let mut frozen = FrozenGraph {
+            offsets: Vec::with_capacity(self.nodes.len() + 1),
+            targets: Vec::with_capacity(self.edges.len()),
+            props: Vec::with_capacity(self.edges.len()),
+            incoming_offsets: Vec::new(),
+            incoming: Vec::new(),
+            original_edges: Vec::with_capacity(self.edges.len()),
+            removed: self.nodes.iter().map(|node| node.removed).collect(),
+            states: self.states,
+        };
+        frozen.offsets.push(0);
+        for node in &self.nodes {
+            for &edge_id in &node.outgoing {
+                frozen.targets.push(self.edges[edge_id].to);
+                frozen.props.push(props[edge_id].take().unwrap());
+                frozen.original_edges.push(edge_id);
             }
+            frozen.offsets.push(frozen.targets.len());
+        }
+        // the incoming edges of each node, grouped by counting the edges that end at each node first
+        frozen.incoming_offsets = vec![0; self.nodes.len() + 1];
+        for &to in &frozen.targets {
+            frozen.incoming_offsets[to + 1] += 1;
+        }
+        for id in 0..self.nodes.len() {
+            frozen.incoming_offsets[id + 1] += frozen.incoming_offsets[id];
         }
-        // then find the cheapest path walking back from the cheapest target via the cheapest incoming edges
-        let mut node_id = best_target?;
-        let mut path = Vec::new();
-        while node_id != source {
-            if let Some(edge_id) = best_incoming[node_id] {
-                path.push(edge_id);
-                node_id = self.edges[edge_id].from;
-            } else {
-                unreachable!();
+        let mut next = frozen.incoming_offsets.clone();
+        frozen.incoming = vec![0; frozen.targets.len()];
+        for (edge_id, &to) in frozen.targets.iter().enumerate() {
+            frozen.incoming[next[to]] = edge_id;
+            next[to] += 1;
+        }
+        frozen
+    }
+    // find the cheapest path to any of the targets, updating the states of the nodes reached by the search
+    pub fn best_path(&mut self, source: NodeId, targets: &[NodeId]) -> Option<Vec<EdgeId>> {
+        match self.try_best_path(source, targets) {
+            Ok(path) => Some(path),
+            Err(Error::NoPath) => None,
+            Err(error) => panic!("failed to find best path: {}", error),
+        }
+    }
+    pub fn try_best_path(
+        &mut self,
+        source: NodeId,
+        targets: &[NodeId],
+    ) -> Result<Vec<EdgeId>, Error> {
+        let scratch = self.explore(Query::new(source, targets))?;
+        let path = scratch.path(self);
+        for (id, state) in scratch.states.into_iter().enumerate() {
+            if let Some(state) = state {
+                self.states[id].update(state);
             }
         }
-        path.reverse();
-        Some(path)
+        path.ok_or(Error::NoPath)
     }
     pub fn node(&self, id: NodeId) -> &Node {
         &self.nodes[id]
     }
+    pub fn incoming(&self, id: NodeId) -> &[EdgeId] {
+        &self.incoming[id]
+    }
     pub fn edge(&self, id: EdgeId) -> &Edge {
         &self.edges[id]
     }
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        id < self.nodes.len() && !self.nodes[id].removed
+    }
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        id < self.edges.len() && !self.edges[id].removed
+    }
+    // ids of the nodes that have not been removed
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| !node.removed)
+            .map(|node| node.id)
+    }
+    // ids of the edges that have not been removed
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.edges
+            .iter()
+            .filter(|edge| !edge.removed)
+            .map(|edge| edge.id)
+    }
+    // number of node ids, including the ids of the removed nodes
     pub fn num_nodes(&self) -> usize {
         self.nodes.len()
     }
+    // number of edge ids, including the ids of the removed edges
     pub fn num_edges(&self) -> usize {
         self.edges.len()
     }
@@ -0,0 +1,140 @@
+use rayon::prelude::*;
+
+use crate::bellman_ford::Relax;
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, Graph, NodeId};
+use crate::search::{Explore, Query};
+use crate::tree::ShortestPathTree;
+
+// the costs of the cheapest paths between all pairs of nodes, row by row: the row of a source holds its cost
+// to each node, None for the nodes it can't reach, and all the rows and columns of the removed nodes are None
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix<Cost> {
+    pub num_nodes: usize,
+    pub costs: Vec<Option<Cost>>,
+}
+
+impl<Cost: Copy> DistanceMatrix<Cost> {
+    pub fn cost(&self, from: NodeId, to: NodeId) -> Option<Cost> {
+        self.costs[from * self.num_nodes + to]
+    }
+    pub fn row(&self, from: NodeId) -> &[Option<Cost>] {
+        &self.costs[from * self.num_nodes..(from + 1) * self.num_nodes]
+    }
+}
+
+// Johnson's algorithm: a Bellman-Ford pass from all the nodes at once gives each node a potential,
+// the cost of the cheapest path to it from anywhere, and with the potentials as A* heuristic, the cost
+// never drops along an edge by more than the heuristic makes up for, so that each source can be searched
+// like Dijkstra even with negative costs; this assumes that the cost added by an edge doesn't depend on
+// the state it is advanced from, as with plain sums of edge costs;
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_all_pairs {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cost of the cheapest path between all pairs of nodes, searching the sources in parallel
+                pub fn all_pairs(&self) -> DistanceMatrix<NodeState::Cost>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_all_pairs() {
+                        Ok(matrix) => matrix,
+                        Err(error) => panic!("failed to find all pairs: {}", error),
+                    }
+                }
+                // fails with NegativeCycle if the cost keeps dropping along a cycle anywhere in the graph
+                pub fn try_all_pairs(&self) -> Result<DistanceMatrix<NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let num_nodes = self.num_nodes();
+                    let mut costs = vec![None; num_nodes * num_nodes];
+                    if num_nodes > 0 {
+                        let heuristic = self.potential_heuristic()?;
+                        costs
+                            .par_chunks_mut(num_nodes)
+                            .enumerate()
+                            .filter(|&(source, _)| self.contains_node(source))
+                            .try_for_each(|(source, row)| {
+                                self.reweighted_tree(source, &heuristic)
+                                    .map(|tree| row.copy_from_slice(&tree.costs))
+                            })?;
+                    }
+                    Ok(DistanceMatrix { num_nodes, costs })
+                }
+                // the same as all_pairs, but streaming the shortest path tree of each source as soon as it is
+                // found, in no particular order, for graphs whose matrix would take too much memory
+                pub fn all_pairs_trees(
+                    &self,
+                ) -> impl ParallelIterator<Item = ShortestPathTree<NodeState::Cost>> + '_
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_all_pairs_trees() {
+                        Ok(trees) => trees.map(|tree| match tree {
+                            Ok(tree) => tree,
+                            Err(error) => panic!("failed to find all pairs: {}", error),
+                        }),
+                        Err(error) => panic!("failed to find all pairs: {}", error),
+                    }
+                }
+                // fails up front with NegativeCycle, before any of the trees is searched
+                pub fn try_all_pairs_trees(
+                    &self,
+                ) -> Result<
+                    impl ParallelIterator<Item = Result<ShortestPathTree<NodeState::Cost>, Error>> + '_,
+                    Error,
+                >
+                where
+                    NodeState: Clone,
+                {
+                    let heuristic = self.potential_heuristic()?;
+                    Ok(self
+                        .node_ids()
+                        .collect::<Vec<_>>()
+                        .into_par_iter()
+                        .map(move |source| self.reweighted_tree(source, &heuristic)))
+                }
+                // the potential of each node subtracted from the highest one, which keeps the heuristic from going
+                // below zero, so that it also works with unsigned costs
+                fn potential_heuristic(&self) -> Result<Vec<NodeState::Cost>, Error> {
+                    let scratch = self.relax(&self.node_ids().collect::<Vec<_>>())?;
+                    let potentials = (0..self.num_nodes())
+                        .map(|id| {
+                            self.tentative(&scratch, id)
+                                .and_then(|state| state.cost())
+                                .unwrap_or_else(Cost::zero)
+                        })
+                        .collect::<Vec<_>>();
+                    let highest = potentials.iter().cloned().max().unwrap_or_else(Cost::zero);
+                    Ok(potentials
+                        .into_iter()
+                        .map(|potential| highest.sub(potential))
+                        .collect())
+                }
+                fn reweighted_tree(
+                    &self,
+                    source: NodeId,
+                    heuristic: &[NodeState::Cost],
+                ) -> Result<ShortestPathTree<NodeState::Cost>, Error> {
+                    let scratch = self.explore(Query {
+                        heuristic: &|id| heuristic[id],
+                        allow_cost_decrease: true,
+                        ..Query::new(source, &[])
+                    })?;
+                    Ok(self.tree(source, scratch))
+                }
+            }
+        )*
+    };
+}
+
+impl_all_pairs!(Graph, FrozenGraph, MappedGraph);
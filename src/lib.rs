@@ -1,3 +1,21 @@
 pub mod advance;
+pub mod bellman_ford;
+pub mod bidirectional;
+pub mod binary;
+pub mod compact;
+pub mod constrained;
+pub mod cost;
+pub mod csv;
+pub mod dimacs;
+pub mod error;
+pub mod frozen;
 pub mod graph;
+pub mod johnson;
+pub mod pareto;
 pub mod priority_queue;
+pub mod search;
+pub mod time_dependent;
+pub mod tree;
+pub mod yen;
+
+pub use error::Error;
@@ -4,18 +4,27 @@ use dijkstra::advance::{Props, State};
 use dijkstra::graph::{Graph, NodeId};
 
 fn main() {
-    let (source, targets) = args();
-    let mut graph: Graph<State, Props> =
-        serde_json::from_reader(std::io::stdin()).expect("failed to deserialise graph");
+    let (source, targets, is_binary, write_binary) = args();
+    let mut graph: Graph<State, Props> = if is_binary {
+        Graph::read_binary(std::io::stdin().lock()).expect("failed to read binary graph")
+    } else {
+        serde_json::from_reader(std::io::stdin()).expect("failed to deserialise graph")
+    };
+    if let Some(path) = write_binary {
+        let file = std::fs::File::create(path).expect("failed to create binary graph file");
+        graph
+            .write_binary(std::io::BufWriter::new(file))
+            .expect("failed to write binary graph");
+        return;
+    }
     graph.state_mut(source).cost = Some(0.0);
-    if let Some(path) = graph.best_path(source, &targets) {
-        println!("path: {:?}", path);
-        let target = graph.edge(*path.last().unwrap()).to;
-        println!("cost: {:?}", graph.state(target).cost.unwrap());
+    if let Some(result) = graph.search(source, &targets) {
+        println!("path: {:?}", result.edges);
+        println!("cost: {:?}", result.cost.0);
     }
 }
 
-fn args() -> (NodeId, Vec<NodeId>) {
+fn args() -> (NodeId, Vec<NodeId>, bool, Option<String>) {
     let matches = App::new("Dijkstra search")
         .arg(
             Arg::with_name("source")
@@ -31,6 +40,17 @@ fn args() -> (NodeId, Vec<NodeId>) {
                 .takes_value(true)
                 .required(true),
         )
+        .arg(
+            Arg::with_name("binary")
+                .long("binary")
+                .help("Read the graph in the binary format instead of JSON"),
+        )
+        .arg(
+            Arg::with_name("write-binary")
+                .long("write-binary")
+                .help("Write the graph to the given file in the binary format instead of searching it")
+                .takes_value(true),
+        )
         .get_matches();
     let source = matches
         .value_of("source")
@@ -43,5 +63,6 @@ fn args() -> (NodeId, Vec<NodeId>) {
         .split(',')
         .map(|target| target.parse::<usize>().expect("failed to parse targets"))
         .collect::<Vec<usize>>();
-    (source, targets)
+    let write_binary = matches.value_of("write-binary").map(String::from);
+    (source, targets, matches.is_present("binary"), write_binary)
 }
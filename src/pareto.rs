@@ -0,0 +1,223 @@
+use rayon::prelude::*;
+
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Dominate, EdgeId, Graph, NodeId, Topology};
+use crate::priority_queue;
+use crate::search::{checked_cost, SearchResult, Stats};
+
+// a path found by the search, ending with the state it has reached its last node with
+struct Label<NodeState> {
+    node: NodeId,
+    state: NodeState,
+    // the label of the path without its last edge, and the last edge; None for the source
+    parent: Option<(LabelId, EdgeId)>,
+}
+
+type LabelId = usize;
+
+// all the labels created by a search, and the labels of the target in the order they were settled
+pub(crate) struct Labels<NodeState> {
+    source: NodeId,
+    labels: Vec<Label<NodeState>>,
+    pub front: Vec<LabelId>,
+    stats: Stats,
+}
+
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_pareto_paths {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Dominate<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the paths from the source to the target that are not dominated by any other path
+                // (the Pareto front), in the order of their cost; instead of the single tentative state of each
+                // node, the search keeps all the states (labels) at each node that are not dominated by another one
+                pub fn pareto_paths(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                ) -> Vec<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_pareto_paths(source, target) {
+                        Ok(paths) => paths,
+                        Err(error) => panic!("failed to find pareto paths: {}", error),
+                    }
+                }
+                pub fn try_pareto_paths(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                ) -> Result<Vec<SearchResult<NodeState, NodeState::Cost>>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let labels = self.explore_labels(source, target, false, |state, props| {
+                        Some(state.advance(props))
+                    })?;
+                    Ok(labels
+                        .front
+                        .iter()
+                        .map(|&label_id| self.labelled_path(&labels, label_id))
+                        .collect())
+                }
+            }
+        )*
+    };
+}
+
+impl_pareto_paths!(Graph, FrozenGraph, MappedGraph);
+
+// the label-setting search itself, on anything with the topology of a graph
+pub(crate) trait ExploreLabels<NodeState, EdgeProps>:
+    Topology<NodeState, EdgeProps> + Sync + Sized
+where
+    NodeState: Sync + Send + Dominate<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+    // label-setting search from the source, where the labels that advance returns None for are dropped;
+    // it stops at the first label of the target if only the cheapest path is needed
+    fn explore_labels<Advance>(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        first_only: bool,
+        advance: Advance,
+    ) -> Result<Labels<NodeState>, Error>
+    where
+        NodeState: Clone,
+        Advance: Fn(&NodeState, &EdgeProps) -> Option<NodeState> + Sync,
+    {
+        for &id in &[source, target] {
+            if !self.contains_node(id) {
+                return Err(Error::InvalidNode(id));
+            }
+        }
+        let mut stats = Stats::default();
+        let mut labels = vec![Label {
+            node: source,
+            state: self.state(source).clone(),
+            parent: None,
+        }];
+        // the labels at each node that are not dominated by any other label so far
+        let mut bags: Vec<Vec<LabelId>> = vec![Vec::new(); self.num_nodes()];
+        bags[source].push(0);
+        let mut queue = priority_queue::Heap::new();
+        queue.insert(0, labels[0].state.cost().unwrap_or_else(Cost::zero));
+        let mut front = Vec::new();
+        // a label costs no more than the labels it dominates, but a label of the same cost may still dominate it
+        // after it has been settled, in which case it is dropped from its bag and from the front, and the labels
+        // it has been extended to get dropped as well when the new label is extended along the same edges
+        while let Some((label_id, _)) = queue.extract_min() {
+            stats.settled += 1;
+            let from = labels[label_id].node;
+            if from == target {
+                front.push(label_id);
+                if first_only {
+                    break;
+                }
+                continue;
+            }
+            let from_state = &labels[label_id].state;
+            let edge_ids = self
+                .outgoing(from)
+                .filter(|&edge_id| self.edge_to(edge_id) != from)
+                .collect::<Vec<_>>();
+            stats.advanced += edge_ids.len();
+            let advanced = edge_ids
+                .par_iter()
+                .filter_map(|&edge_id| {
+                    advance(from_state, self.props(edge_id)).map(|state| (edge_id, state))
+                })
+                .collect::<Vec<_>>();
+            for (edge_id, new_state) in advanced {
+                let to = self.edge_to(edge_id);
+                let cost = checked_cost(&new_state, edge_id)?;
+                if bags[to]
+                    .iter()
+                    .any(|&id| labels[id].state.dominates(&new_state))
+                {
+                    continue;
+                }
+                // the labels still in the queue that are dominated by the new one are not going to be expanded
+                let dominated = bags[to]
+                    .iter()
+                    .cloned()
+                    .filter(|&id| new_state.dominates(&labels[id].state))
+                    .collect::<Vec<_>>();
+                for &id in &dominated {
+                    queue.remove(id);
+                }
+                bags[to].retain(|id| !dominated.contains(id));
+                front.retain(|id| !dominated.contains(id));
+                let new_label_id = labels.len();
+                labels.push(Label {
+                    node: to,
+                    state: new_state,
+                    parent: Some((label_id, edge_id)),
+                });
+                bags[to].push(new_label_id);
+                queue.insert(new_label_id, cost);
+            }
+        }
+        Ok(Labels {
+            source,
+            labels,
+            front,
+            stats,
+        })
+    }
+    // walk back from the label via the labels it extends, keeping the states along the path
+    fn labelled_path(
+        &self,
+        labels: &Labels<NodeState>,
+        label_id: LabelId,
+    ) -> SearchResult<NodeState, NodeState::Cost>
+    where
+        NodeState: Clone,
+    {
+        let mut states: Vec<Option<NodeState>> = (0..self.num_nodes()).map(|_| None).collect();
+        let mut edges = Vec::new();
+        let mut id = label_id;
+        loop {
+            let label = &labels.labels[id];
+            states[label.node] = Some(label.state.clone());
+            match label.parent {
+                Some((parent, edge_id)) => {
+                    edges.push(edge_id);
+                    id = parent;
+                }
+                None => break,
+            }
+        }
+        edges.reverse();
+        let label = &labels.labels[label_id];
+        SearchResult {
+            source: labels.source,
+            target: label.node,
+            nodes: std::iter::once(labels.source)
+                .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+                .collect(),
+            edges,
+            cost: label.state.cost().unwrap_or_else(Cost::zero),
+            state: label.state.clone(),
+            states,
+            stats: labels.stats,
+        }
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> ExploreLabels<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: Topology<NodeState, EdgeProps> + Sync,
+    NodeState: Sync + Send + Dominate<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+}
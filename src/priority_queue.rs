@@ -1,57 +1,178 @@
-// priority queue based on binary heap for efficient access to id with the lowest cost
-#[derive(Debug, Default)]
-pub struct Heap<Cost> {
-    items: Vec<Item<Cost>>,
+// priority queues for efficient access to id with the lowest cost; the searches are generic over this trait
+pub trait PriorityQueue<Cost> {
+    // whether the queue can't take a cost below the last extracted one, which the searches check
+    // before inserting instead of leaving it to the queue to panic
+    const IS_MONOTONE: bool = false;
+    fn new() -> Self;
+    fn is_empty(&self) -> bool;
+    fn len(&self) -> usize;
+    fn peek(&self) -> Option<(Id, Cost)>;
+    // insert the id, or lower its cost if it is already in the queue with a higher one; a higher cost never
+    // replaces a lower one, so that the lowest cost inserted for an id is the first to come out for it;
+    // the queues that don't update in place keep all the items, and the user has to discard the later ones
+    fn insert(&mut self, id: Id, cost: Cost);
+    fn extract_min(&mut self) -> Option<(Id, Cost)>;
+}
+
+// costs that can be mapped to integers preserving their order, as required by the monotone queues
+pub trait Key: Clone + PartialOrd {
+    fn key(&self) -> u64;
+}
+
+// the integer costs, whose keys are the costs themselves, so that there are no more keys between two costs
+// than there are costs, as the bucket queue requires
+pub trait IntegerKey: Key {}
+
+// for non-negative floats, the order of the bit patterns is the order of the numbers
+impl Key for f64 {
+    fn key(&self) -> u64 {
+        debug_assert!(*self >= 0.0, "negative or NaN cost {}", self);
+        self.to_bits()
+    }
+}
+
+macro_rules! impl_key {
+    ($($t:ty),*) => {
+        $(
+            impl Key for $t {
+                fn key(&self) -> u64 {
+                    *self as u64
+                }
+            }
+
+            impl IntegerKey for $t {}
+        )*
+    };
 }
 
+impl_key!(u8, u16, u32, u64, usize);
+
+type Id = usize;
+
 #[derive(Debug, Clone)]
 struct Item<Cost> {
     id: Id,
     cost: Cost,
 }
 
-type Id = usize;
+// priority queue based on d-ary heap; the position of each id in the heap is kept,
+// so that its cost can be updated in place
+#[derive(Debug, Default)]
+pub struct DaryHeap<Cost, const D: usize> {
+    items: Vec<Item<Cost>>,
+    // index of each id in items, None for the ids that are not in the heap
+    positions: Vec<Option<usize>>,
+}
+
+// priority queue based on binary heap
+pub type Heap<Cost> = DaryHeap<Cost, 2>;
 
-impl<Cost: Clone + PartialOrd> Heap<Cost> {
+impl<Cost: Clone + PartialOrd, const D: usize> DaryHeap<Cost, D> {
     pub fn new() -> Self {
-        Heap { items: Vec::new() }
+        DaryHeap {
+            items: Vec::new(),
+            positions: Vec::new(),
+        }
     }
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn contains(&self, id: Id) -> bool {
+        self.position(id).is_some()
+    }
+    pub fn peek(&self) -> Option<(Id, Cost)> {
+        self.items.first().map(|item| (item.id, item.cost.clone()))
+    }
+    // insert the id, or lower its cost if it is already in the heap with a higher one, as PriorityQueue::insert
     pub fn insert(&mut self, id: Id, cost: Cost) {
-        self.items.push(Item { id, cost });
-        self.promote(self.items.len() - 1);
+        if !self.decrease_key(id, cost.clone()) && !self.contains(id) {
+            self.set_cost(id, cost);
+        }
     }
-    pub fn extract_min(&mut self) -> Option<(Id, Cost)> {
-        match self.items.len() {
-            0 => None,
-            1 => {
-                let item = self.items.pop().unwrap();
-                Some((item.id, item.cost))
+    // insert the id, or update its cost if it is already in the heap, whether the new cost is lower or higher
+    pub fn set_cost(&mut self, id: Id, cost: Cost) {
+        match self.position(id) {
+            Some(index) => {
+                let is_lower = cost < self.items[index].cost;
+                self.items[index].cost = cost;
+                if is_lower {
+                    self.promote(index);
+                } else {
+                    self.demote(index);
+                }
+            }
+            None => {
+                if id >= self.positions.len() {
+                    self.positions.resize(id + 1, None);
+                }
+                self.items.push(Item { id, cost });
+                self.positions[id] = Some(self.items.len() - 1);
+                self.promote(self.items.len() - 1);
             }
-            _ => {
-                let item = self.items[0].clone();
-                self.items[0] = self.items.pop().unwrap();
-                self.demote(0);
-                Some((item.id, item.cost))
+        }
+    }
+    // lower the cost of an id in the heap, returning false if the id is not in the heap
+    // or if its cost is already lower
+    pub fn decrease_key(&mut self, id: Id, cost: Cost) -> bool {
+        match self.position(id) {
+            Some(index) if cost < self.items[index].cost => {
+                self.items[index].cost = cost;
+                self.promote(index);
+                true
             }
+            _ => false,
         }
     }
+    pub fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        if self.is_empty() {
+            None
+        } else {
+            self.remove_at(0)
+        }
+    }
+    pub fn remove(&mut self, id: Id) -> Option<Cost> {
+        let index = self.position(id)?;
+        self.remove_at(index).map(|(_, cost)| cost)
+    }
+    fn position(&self, id: Id) -> Option<usize> {
+        self.positions.get(id).cloned().flatten()
+    }
+    fn remove_at(&mut self, index: usize) -> Option<(Id, Cost)> {
+        let last = self.items.len() - 1;
+        self.swap(index, last);
+        let item = self.items.pop()?;
+        self.positions[item.id] = None;
+        if index < self.items.len() {
+            // the last item that took the place of the removed one might need to go either way
+            self.promote(index);
+            self.demote(index);
+        }
+        Some((item.id, item.cost))
+    }
+    fn swap(&mut self, a: usize, b: usize) {
+        self.items.swap(a, b);
+        self.positions[self.items[a].id] = Some(a);
+        self.positions[self.items[b].id] = Some(b);
+    }
     // demote more expensive parent towards the bottom of the heap
-    fn demote(&mut self, mut parent: Id) {
+    fn demote(&mut self, mut parent: usize) {
         loop {
-            match self.children(parent) {
-                (Some(left), Some(right))
-                    if self.items[right].cost < self.items[left].cost
-                        && self.items[parent].cost > self.items[right].cost =>
-                {
-                    self.items.swap(parent, right);
-                    parent = right;
-                }
-                (Some(left), _) if self.items[parent].cost > self.items[left].cost => {
-                    self.items.swap(parent, left);
-                    parent = left;
+            let first = D * parent + 1;
+            let last = std::cmp::min(first + D, self.items.len());
+            let cheapest =
+                (first..last).fold(None, |cheapest: Option<usize>, child| match cheapest {
+                    Some(cheapest) if self.items[cheapest].cost <= self.items[child].cost => {
+                        Some(cheapest)
+                    }
+                    _ => Some(child),
+                });
+            match cheapest {
+                Some(child) if self.items[parent].cost > self.items[child].cost => {
+                    self.swap(parent, child);
+                    parent = child;
                 }
                 _ => {
                     return;
@@ -60,35 +181,380 @@ impl<Cost: Clone + PartialOrd> Heap<Cost> {
         }
     }
     // promote less expensive child towards the top of the heap
-    fn promote(&mut self, mut child: Id) {
+    fn promote(&mut self, mut child: usize) {
+        while child > 0 {
+            let parent = (child - 1) / D;
+            if self.items[child].cost >= self.items[parent].cost {
+                return;
+            }
+            self.swap(child, parent);
+            child = parent;
+        }
+    }
+}
+
+impl<Cost: Clone + PartialOrd, const D: usize> PriorityQueue<Cost> for DaryHeap<Cost, D> {
+    fn new() -> Self {
+        DaryHeap::new()
+    }
+    fn is_empty(&self) -> bool {
+        DaryHeap::is_empty(self)
+    }
+    fn len(&self) -> usize {
+        DaryHeap::len(self)
+    }
+    fn peek(&self) -> Option<(Id, Cost)> {
+        DaryHeap::peek(self)
+    }
+    fn insert(&mut self, id: Id, cost: Cost) {
+        DaryHeap::insert(self, id, cost);
+    }
+    fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        DaryHeap::extract_min(self)
+    }
+}
+
+// priority queue based on binary heap that keeps all the items inserted for an id,
+// leaving it to the user to discard the old more expensive ones when they get to the front
+#[derive(Debug, Default)]
+pub struct LazyHeap<Cost> {
+    items: Vec<Item<Cost>>,
+}
+
+impl<Cost: Clone + PartialOrd> PriorityQueue<Cost> for LazyHeap<Cost> {
+    fn new() -> Self {
+        LazyHeap { items: Vec::new() }
+    }
+    fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+    fn peek(&self) -> Option<(Id, Cost)> {
+        self.items.first().map(|item| (item.id, item.cost.clone()))
+    }
+    fn insert(&mut self, id: Id, cost: Cost) {
+        self.items.push(Item { id, cost });
+        let mut child = self.items.len() - 1;
+        while child > 0 {
+            let parent = (child - 1) / 2;
+            if self.items[child].cost >= self.items[parent].cost {
+                break;
+            }
+            self.items.swap(child, parent);
+            child = parent;
+        }
+    }
+    fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let item = self.items.swap_remove(0);
+        let mut parent = 0;
         loop {
-            match self.parent(child) {
-                Some(parent) if self.items[child].cost < self.items[parent].cost => {
-                    self.items.swap(child, parent);
-                    child = parent;
-                }
-                _ => {
-                    return;
-                }
+            let left = 2 * parent + 1;
+            let right = left + 1;
+            let mut smallest = parent;
+            if left < self.items.len() && self.items[left].cost < self.items[smallest].cost {
+                smallest = left;
+            }
+            if right < self.items.len() && self.items[right].cost < self.items[smallest].cost {
+                smallest = right;
             }
+            if smallest == parent {
+                break;
+            }
+            self.items.swap(parent, smallest);
+            parent = smallest;
         }
+        Some((item.id, item.cost))
     }
-    fn parent(&self, child: Id) -> Option<Id> {
-        if child == 0 {
-            None
-        } else {
-            Some((child - 1) / 2)
+}
+
+// priority queue based on pairing heap, a tree where each node is cheaper than its children,
+// which makes inserting and lowering the cost of an id cheap, leaving the work to extract_min
+#[derive(Debug, Default)]
+pub struct PairingHeap<Cost> {
+    nodes: Vec<PairingNode<Cost>>,
+    root: Option<usize>,
+    len: usize,
+    // index of each id in nodes, None for the ids that are not in the heap
+    positions: Vec<Option<usize>>,
+    // indices of the nodes that can be reused
+    free: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct PairingNode<Cost> {
+    item: Item<Cost>,
+    child: Option<usize>,
+    next: Option<usize>,
+    // the parent for the first child, and the previous sibling for the others
+    prev: Option<usize>,
+}
+
+impl<Cost: Clone + PartialOrd> PairingHeap<Cost> {
+    // lower the cost of an id in the heap by cutting its subtree and merging it with the root
+    pub fn decrease_key(&mut self, id: Id, cost: Cost) -> bool {
+        let index = match self.positions.get(id).cloned().flatten() {
+            Some(index) if cost < self.nodes[index].item.cost => index,
+            _ => return false,
+        };
+        self.nodes[index].item.cost = cost;
+        if self.root != Some(index) {
+            let PairingNode { next, prev, .. } = self.nodes[index];
+            let prev = prev.unwrap();
+            if self.nodes[prev].child == Some(index) {
+                self.nodes[prev].child = next;
+            } else {
+                self.nodes[prev].next = next;
+            }
+            if let Some(next) = next {
+                self.nodes[next].prev = Some(prev);
+            }
+            self.nodes[index].next = None;
+            self.nodes[index].prev = None;
+            self.root = Some(self.meld(self.root.unwrap(), index));
         }
+        true
     }
-    fn children(&self, parent: Id) -> (Option<Id>, Option<Id>) {
-        let left = 2 * parent + 1;
-        let right = left + 1;
-        if right < self.items.len() {
-            (Some(left), Some(right))
-        } else if left < self.items.len() {
-            (Some(left), None)
+    // make the more expensive of the two roots the first child of the other one
+    fn meld(&mut self, a: usize, b: usize) -> usize {
+        let (parent, child) = if self.nodes[b].item.cost < self.nodes[a].item.cost {
+            (b, a)
         } else {
-            (None, None)
+            (a, b)
+        };
+        let first = self.nodes[parent].child;
+        self.nodes[child].next = first;
+        self.nodes[child].prev = Some(parent);
+        if let Some(first) = first {
+            self.nodes[first].prev = Some(child);
+        }
+        self.nodes[parent].child = Some(child);
+        parent
+    }
+    // merge the children in pairs from left to right, then the pairs from right to left
+    fn merge_children(&mut self, first: Option<usize>) -> Option<usize> {
+        let mut pairs = Vec::new();
+        let mut current = first;
+        while let Some(a) = current {
+            let b = self.nodes[a].next;
+            current = b.and_then(|b| self.nodes[b].next);
+            self.nodes[a].next = None;
+            self.nodes[a].prev = None;
+            match b {
+                Some(b) => {
+                    self.nodes[b].next = None;
+                    self.nodes[b].prev = None;
+                    pairs.push(self.meld(a, b));
+                }
+                None => pairs.push(a),
+            }
+        }
+        let last = pairs.pop()?;
+        Some(
+            pairs
+                .into_iter()
+                .rev()
+                .fold(last, |root, pair| self.meld(pair, root)),
+        )
+    }
+}
+
+impl<Cost: Clone + PartialOrd> PriorityQueue<Cost> for PairingHeap<Cost> {
+    fn new() -> Self {
+        PairingHeap {
+            nodes: Vec::new(),
+            root: None,
+            len: 0,
+            positions: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+    fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn peek(&self) -> Option<(Id, Cost)> {
+        self.root.map(|root| {
+            let item = &self.nodes[root].item;
+            (item.id, item.cost.clone())
+        })
+    }
+    fn insert(&mut self, id: Id, cost: Cost) {
+        if self.positions.get(id).cloned().flatten().is_some() {
+            // a higher cost leaves the id as it is
+            self.decrease_key(id, cost);
+            return;
+        }
+        let node = PairingNode {
+            item: Item { id, cost },
+            child: None,
+            next: None,
+            prev: None,
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        if id >= self.positions.len() {
+            self.positions.resize(id + 1, None);
+        }
+        self.positions[id] = Some(index);
+        self.len += 1;
+        self.root = Some(match self.root {
+            Some(root) => self.meld(root, index),
+            None => index,
+        });
+    }
+    fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        let root = self.root?;
+        let child = self.nodes[root].child;
+        self.root = self.merge_children(child);
+        let item = self.nodes[root].item.clone();
+        self.positions[item.id] = None;
+        self.free.push(root);
+        self.len -= 1;
+        Some((item.id, item.cost))
+    }
+}
+
+// monotone priority queue based on radix heap: the items are kept in buckets by the highest bit
+// in which their key differs from the key of the last extracted item, so that no item can be
+// cheaper than the last extracted one; it keeps the old more expensive items for an id
+#[derive(Debug)]
+pub struct RadixHeap<Cost> {
+    buckets: Vec<Vec<Item<Cost>>>,
+    last: u64,
+    len: usize,
+}
+
+impl<Cost: Key> RadixHeap<Cost> {
+    fn bucket(&self, key: u64) -> usize {
+        (64 - (key ^ self.last).leading_zeros()) as usize
+    }
+    // make the cheapest item the last extracted one, moving the items of its bucket to the lower buckets
+    fn redistribute(&mut self) {
+        if !self.buckets[0].is_empty() {
+            return;
+        }
+        let index = match self.buckets.iter().position(|bucket| !bucket.is_empty()) {
+            Some(index) => index,
+            None => return,
+        };
+        let items = std::mem::take(&mut self.buckets[index]);
+        self.last = items.iter().map(|item| item.cost.key()).min().unwrap();
+        for item in items {
+            let bucket = self.bucket(item.cost.key());
+            self.buckets[bucket].push(item);
+        }
+    }
+}
+
+impl<Cost: Key> PriorityQueue<Cost> for RadixHeap<Cost> {
+    const IS_MONOTONE: bool = true;
+    fn new() -> Self {
+        RadixHeap {
+            buckets: (0..=64).map(|_| Vec::new()).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn peek(&self) -> Option<(Id, Cost)> {
+        let bucket = self.buckets.iter().find(|bucket| !bucket.is_empty())?;
+        let item = bucket.iter().min_by_key(|item| item.cost.key())?;
+        Some((item.id, item.cost.clone()))
+    }
+    fn insert(&mut self, id: Id, cost: Cost) {
+        let key = cost.key();
+        assert!(key >= self.last, "radix heap requires monotone costs");
+        let bucket = self.bucket(key);
+        self.buckets[bucket].push(Item { id, cost });
+        self.len += 1;
+    }
+    fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        self.redistribute();
+        let item = self.buckets[0].pop()?;
+        self.len -= 1;
+        Some((item.id, item.cost))
+    }
+}
+
+// monotone priority queue with a bucket for each key (Dial's algorithm), for integer costs with small
+// increments, since the buckets between the last extracted key and the highest key are all allocated
+// (which rules out the floats, whose keys are their bit patterns); it keeps the old more expensive items for an id
+#[derive(Debug)]
+pub struct BucketQueue<Cost> {
+    // the bucket of key is at index key - first
+    buckets: std::collections::VecDeque<Vec<Item<Cost>>>,
+    first: u64,
+    // the key of the last extracted item
+    last: u64,
+    len: usize,
+}
+
+impl<Cost: IntegerKey> PriorityQueue<Cost> for BucketQueue<Cost> {
+    const IS_MONOTONE: bool = true;
+    fn new() -> Self {
+        BucketQueue {
+            buckets: std::collections::VecDeque::new(),
+            first: 0,
+            last: 0,
+            len: 0,
+        }
+    }
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn peek(&self) -> Option<(Id, Cost)> {
+        let bucket = self.buckets.iter().find(|bucket| !bucket.is_empty())?;
+        bucket.last().map(|item| (item.id, item.cost.clone()))
+    }
+    fn insert(&mut self, id: Id, cost: Cost) {
+        let key = cost.key();
+        assert!(key >= self.last, "bucket queue requires monotone costs");
+        if self.len == 0 {
+            self.buckets.clear();
+            self.first = key;
+        }
+        while key < self.first {
+            self.buckets.push_front(Vec::new());
+            self.first -= 1;
+        }
+        let index = (key - self.first) as usize;
+        if index >= self.buckets.len() {
+            self.buckets.resize_with(index + 1, Vec::new);
+        }
+        self.buckets[index].push(Item { id, cost });
+        self.len += 1;
+    }
+    fn extract_min(&mut self) -> Option<(Id, Cost)> {
+        while self.buckets.front()?.is_empty() {
+            self.buckets.pop_front();
+            self.first += 1;
         }
+        let item = self.buckets.front_mut()?.pop()?;
+        self.last = self.first;
+        self.len -= 1;
+        Some((item.id, item.cost))
     }
 }
@@ -0,0 +1,675 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, EdgeId, Graph, NodeId, Topology};
+use crate::priority_queue::{self, PriorityQueue};
+
+// outcome of a search that leaves the graph untouched: the cheapest path to the target that was reached and
+// the tentative states of the nodes reached by the search (None for the nodes never reached)
+#[derive(Debug, Clone)]
+pub struct SearchResult<NodeState, Cost> {
+    // the source the path starts from, which is one of several for the multi-source search
+    pub source: NodeId,
+    pub target: NodeId,
+    pub nodes: Vec<NodeId>,
+    pub edges: Vec<EdgeId>,
+    pub cost: Cost,
+    pub state: NodeState,
+    pub states: Vec<Option<NodeState>>,
+    pub stats: Stats,
+}
+
+// how much work the search has done
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub settled: usize,
+    pub advanced: usize,
+    pub stale: usize,
+}
+
+// restrictions on the edges and nodes that a search may use, leaving the graph as it is;
+// the filters are applied in parallel along with advance, so they have to be Sync
+pub struct SearchOptions<'a, EdgeProps, Cost> {
+    // the edges for which the filter returns false are not advanced along
+    pub edge_filter: Option<EdgeFilter<'a, EdgeProps>>,
+    // the nodes for which the filter returns false are not entered
+    pub node_filter: Option<NodeFilter<'a>>,
+    pub budget: Budget<Cost>,
+    // fail with CostDecrease if advancing a state along an edge lowers its cost, which the search can't handle,
+    // since it never reopens a settled node; for this, the states are also advanced along the edges
+    // to the settled nodes, which are skipped otherwise; without it, the path found may not be the cheapest
+    pub detect_cost_decrease: bool,
+}
+
+pub type EdgeFilter<'a, EdgeProps> = Box<dyn Fn(EdgeId, &EdgeProps) -> bool + Sync + 'a>;
+pub type NodeFilter<'a> = Box<dyn Fn(NodeId) -> bool + Sync + 'a>;
+
+impl<'a, EdgeProps, Cost> Default for SearchOptions<'a, EdgeProps, Cost> {
+    fn default() -> Self {
+        SearchOptions {
+            edge_filter: None,
+            node_filter: None,
+            budget: Budget::default(),
+            detect_cost_decrease: false,
+        }
+    }
+}
+
+// limits on the work a search may do before giving up, since each advance may be expensive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget<Cost> {
+    // no node is settled or reached as a target with a cost above this one
+    pub max_cost: Option<Cost>,
+    pub max_settled: Option<usize>,
+    pub max_advanced: Option<usize>,
+    pub deadline: Option<Instant>,
+}
+
+impl<Cost> Default for Budget<Cost> {
+    fn default() -> Self {
+        Budget {
+            max_cost: None,
+            max_settled: None,
+            max_advanced: None,
+            deadline: None,
+        }
+    }
+}
+
+impl<Cost: PartialOrd> Budget<Cost> {
+    // whether a node with the given cost is beyond the limit, the targets included
+    fn exceeds_cost(&self, cost: &Cost) -> bool {
+        self.max_cost
+            .as_ref()
+            .is_some_and(|max_cost| cost > max_cost)
+    }
+    // the limit reached before settling another node
+    fn exceeded(&self, stats: &Stats) -> Option<Limit> {
+        if self
+            .max_settled
+            .is_some_and(|max_settled| stats.settled >= max_settled)
+        {
+            Some(Limit::Settled)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Limit::Deadline)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    Cost,
+    Settled,
+    Advanced,
+    Deadline,
+}
+
+// where a search stood when it ran out of budget: the nodes that had been reached but not settled,
+// the most promising first, and the path to the most promising one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frontier {
+    pub limit: Limit,
+    pub nodes: Vec<NodeId>,
+    pub path: Vec<EdgeId>,
+    pub stats: Stats,
+}
+
+// what a single search starts from, where it goes, and which edges it may use on the way
+pub(crate) struct Query<'a, NodeState, Cost> {
+    // the state to start from at each source, None for the one stored in the graph
+    pub sources: Vec<(NodeId, Option<NodeState>)>,
+    pub goal: Goal<'a, NodeState>,
+    pub heuristic: &'a dyn Fn(NodeId) -> Cost,
+    // in debug builds, check that the heuristic is consistent along each advanced edge;
+    // only for the heuristics given to A*, since the zero heuristic of the other searches always is
+    pub check_heuristic: bool,
+    pub is_allowed: &'a (dyn Fn(EdgeId) -> bool + Sync),
+    pub budget: Budget<Cost>,
+    pub detect_cost_decrease: bool,
+    // let the cost drop along the edges, negative costs included, as long as the heuristic makes up for it;
+    // the heuristic is trusted to be consistent then, since the potentials of Johnson's all-pairs search
+    // are consistent only up to the rounding of float costs
+    pub allow_cost_decrease: bool,
+}
+
+impl<'a, NodeState, Cost: self::Cost> Query<'a, NodeState, Cost> {
+    pub fn new(source: NodeId, targets: &'a [NodeId]) -> Self {
+        Query::with_sources(vec![(source, None)], targets)
+    }
+    pub fn with_sources(sources: Vec<(NodeId, Option<NodeState>)>, targets: &'a [NodeId]) -> Self {
+        Query {
+            sources,
+            goal: Goal::Targets(targets),
+            heuristic: &|_| Cost::zero(),
+            check_heuristic: false,
+            is_allowed: &|_| true,
+            budget: Budget::default(),
+            detect_cost_decrease: false,
+            allow_cost_decrease: false,
+        }
+    }
+}
+
+// where a search may stop: at the first node that is one of the targets, or that satisfies the predicate
+pub(crate) enum Goal<'a, NodeState> {
+    Targets(&'a [NodeId]),
+    Predicate(&'a dyn Fn(NodeId, &NodeState) -> bool),
+}
+
+// per-search bookkeeping, kept apart from the graph so that concurrent searches don't interfere
+pub(crate) struct Scratch<NodeState> {
+    // the sources start from the states given to the search, or from the ones stored in the graph
+    pub is_source: Vec<bool>,
+    pub target: Option<NodeId>,
+    pub best_incoming: Vec<Option<EdgeId>>,
+    pub states: Vec<Option<NodeState>>,
+    pub stats: Stats,
+}
+
+impl<NodeState> Scratch<NodeState> {
+    // the sources and the nodes with an incoming edge found by the search
+    fn is_reached(&self, id: NodeId) -> bool {
+        self.best_incoming[id].is_some() || self.states[id].is_some() || self.is_source[id]
+    }
+    // walk back from the target via the cheapest incoming edges
+    pub fn path<EdgeProps>(
+        &self,
+        graph: &impl Topology<NodeState, EdgeProps>,
+    ) -> Option<Vec<EdgeId>> {
+        Some(self.path_to(graph, self.target?))
+    }
+    pub fn path_to<EdgeProps>(
+        &self,
+        graph: &impl Topology<NodeState, EdgeProps>,
+        id: NodeId,
+    ) -> Vec<EdgeId> {
+        let mut node_id = id;
+        let mut path = Vec::new();
+        while let Some(edge_id) = self.best_incoming[node_id] {
+            path.push(edge_id);
+            node_id = graph.edge_from(edge_id);
+        }
+        path.reverse();
+        path
+    }
+}
+
+// the cost of a state advanced along an edge, which has to be a non-negative number
+pub(crate) fn checked_cost<NodeState, EdgeProps>(
+    state: &NodeState,
+    edge_id: EdgeId,
+) -> Result<NodeState::Cost, Error>
+where
+    NodeState: Advance<NodeState, EdgeProps>,
+{
+    match state.cost() {
+        None => Err(Error::NoCostOnAdvancedState(edge_id)),
+        Some(cost) if cost.is_nan() => Err(Error::NaNCost(edge_id)),
+        Some(cost) if cost < Cost::zero() => Err(Error::NegativeCost(edge_id)),
+        Some(cost) => Ok(cost),
+    }
+}
+
+// these searches are the same on Graph, FrozenGraph and MappedGraph, which only differ in how they store the edges
+macro_rules! impl_search {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cheapest path to any of the targets without modifying the node states of the graph
+                pub fn search(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_search(source, targets) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                pub fn try_search(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let scratch = self.explore(Query::new(source, targets))?;
+                    self.result(scratch)
+                }
+                // same as search, but the search stops at the first node for which the goal predicate holds,
+                // given the id of the node and the state it has been reached with
+                pub fn search_goal<Predicate>(
+                    &self,
+                    source: NodeId,
+                    is_goal: Predicate,
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                    Predicate: Fn(NodeId, &NodeState) -> bool,
+                {
+                    match self.try_search_goal(source, is_goal) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                pub fn try_search_goal<Predicate>(
+                    &self,
+                    source: NodeId,
+                    is_goal: Predicate,
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                    Predicate: Fn(NodeId, &NodeState) -> bool,
+                {
+                    let scratch = self.explore(Query {
+                        goal: Goal::Predicate(&is_goal),
+                        ..Query::new(source, &[])
+                    })?;
+                    self.result(scratch)
+                }
+                // same as search, but starting from several sources at once, each with its own initial state;
+                // the path starts from the source from which the nearest target is the cheapest to reach
+                pub fn search_multi_source(
+                    &self,
+                    sources: &[(NodeId, NodeState)],
+                    targets: &[NodeId],
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_search_multi_source(sources, targets) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                pub fn try_search_multi_source(
+                    &self,
+                    sources: &[(NodeId, NodeState)],
+                    targets: &[NodeId],
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let sources = sources
+                        .iter()
+                        .map(|(id, state)| (*id, Some(state.clone())))
+                        .collect();
+                    let scratch = self.explore(Query::with_sources(sources, targets))?;
+                    self.result(scratch)
+                }
+                // same as search, but only along the edges and through the nodes allowed by the options
+                pub fn search_with(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                    options: &SearchOptions<EdgeProps, NodeState::Cost>,
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_search_with(source, targets, options) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                pub fn try_search_with(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                    options: &SearchOptions<EdgeProps, NodeState::Cost>,
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    self.try_search_with_queue::<priority_queue::Heap<NodeState::Cost>>(
+                        source, targets, options,
+                    )
+                }
+                // same as search_with, but with the given priority queue instead of the binary heap;
+                // with a monotone queue, it fails with CostDecrease if a key drops below the last extracted one
+                pub fn try_search_with_queue<Queue>(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                    options: &SearchOptions<EdgeProps, NodeState::Cost>,
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                    Queue: PriorityQueue<NodeState::Cost>,
+                {
+                    let is_allowed = |edge_id| {
+                        let is_allowed_edge = match &options.edge_filter {
+                            Some(filter) => filter(edge_id, self.props(edge_id)),
+                            None => true,
+                        };
+                        let is_allowed_node = match &options.node_filter {
+                            Some(filter) => filter(self.edge_to(edge_id)),
+                            None => true,
+                        };
+                        is_allowed_edge && is_allowed_node
+                    };
+                    let scratch = self.explore_in::<Queue>(Query {
+                        is_allowed: &is_allowed,
+                        budget: options.budget,
+                        detect_cost_decrease: options.detect_cost_decrease,
+                        ..Query::new(source, targets)
+                    })?;
+                    self.result(scratch)
+                }
+                // A* search: same as search, but the nodes are explored in the order of their cost plus
+                // the estimate of the remaining cost to the nearest target given by the heuristic,
+                // which must be consistent (monotone) for the path to be the cheapest: the estimate at a node
+                // is never more than the cost of an edge from it plus the estimate at the end of the edge,
+                // since the closed nodes are not reopened; a consistent heuristic also never overestimates
+                pub fn search_astar<Heuristic>(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                    heuristic: Heuristic,
+                ) -> Option<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                    Heuristic: Fn(NodeId) -> NodeState::Cost,
+                {
+                    match self.try_search_astar(source, targets, heuristic) {
+                        Ok(result) => Some(result),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to search: {}", error),
+                    }
+                }
+                // in debug builds, the heuristic is also checked for consistency along each advanced edge
+                pub fn try_search_astar<Heuristic>(
+                    &self,
+                    source: NodeId,
+                    targets: &[NodeId],
+                    heuristic: Heuristic,
+                ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+                where
+                    NodeState: Clone,
+                    Heuristic: Fn(NodeId) -> NodeState::Cost,
+                {
+                    let scratch = self.explore(Query {
+                        heuristic: &heuristic,
+                        check_heuristic: true,
+                        ..Query::new(source, targets)
+                    })?;
+                    self.result(scratch)
+                }
+            }
+        )*
+    };
+}
+
+impl_search!(Graph, FrozenGraph, MappedGraph);
+
+// the search itself, on anything with the topology of a graph
+pub(crate) trait Explore<NodeState, EdgeProps>:
+    Topology<NodeState, EdgeProps> + Sync + Sized
+where
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+    fn result(
+        &self,
+        scratch: Scratch<NodeState>,
+    ) -> Result<SearchResult<NodeState, NodeState::Cost>, Error>
+    where
+        NodeState: Clone,
+    {
+        let edges = scratch.path(self).ok_or(Error::NoPath)?;
+        let target = scratch.target.ok_or(Error::NoPath)?;
+        let source = match edges.first() {
+            Some(&edge_id) => self.edge_from(edge_id),
+            None => target,
+        };
+        let nodes = std::iter::once(source)
+            .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+            .collect();
+        let state = self
+            .tentative(&scratch, target)
+            .ok_or(Error::NoPath)?
+            .clone();
+        Ok(SearchResult {
+            source,
+            target,
+            nodes,
+            edges,
+            cost: state.cost().unwrap_or_else(Cost::zero),
+            state,
+            states: scratch.states,
+            stats: scratch.stats,
+        })
+    }
+    fn explore(
+        &self,
+        query: Query<NodeState, NodeState::Cost>,
+    ) -> Result<Scratch<NodeState>, Error> {
+        self.explore_in::<priority_queue::Heap<NodeState::Cost>>(query)
+    }
+    fn explore_in<Queue>(
+        &self,
+        query: Query<NodeState, NodeState::Cost>,
+    ) -> Result<Scratch<NodeState>, Error>
+    where
+        Queue: PriorityQueue<NodeState::Cost>,
+    {
+        let Query {
+            sources,
+            goal,
+            heuristic,
+            check_heuristic,
+            is_allowed,
+            budget,
+            detect_cost_decrease,
+            allow_cost_decrease,
+        } = query;
+        let targets = match goal {
+            Goal::Targets(targets) => targets,
+            Goal::Predicate(_) => &[],
+        };
+        for &id in sources.iter().map(|(id, _)| id).chain(targets) {
+            if !self.contains_node(id) {
+                return Err(Error::InvalidNode(id));
+            }
+        }
+        // the targets as a bitset, so that each node taken from the queue is checked in constant time
+        let mut is_target = vec![false; self.num_nodes()];
+        for &id in targets {
+            is_target[id] = true;
+        }
+        let mut scratch = Scratch {
+            is_source: vec![false; self.num_nodes()],
+            target: None,
+            best_incoming: vec![None; self.num_nodes()],
+            states: (0..self.num_nodes()).map(|_| None).collect(),
+            stats: Stats::default(),
+        };
+        // from the sources, use breadth-first search to find the cheapest incoming edge for each node;
+        // a source that is also a target is the first to be taken from the queue, unless another one is cheaper
+        let mut is_closed = vec![false; self.num_nodes()];
+        let mut queue = Queue::new();
+        let mut seeded: Vec<(NodeId, NodeState::Cost)> = Vec::new();
+        for (source, source_state) in sources {
+            let source_cost = source_state
+                .as_ref()
+                .unwrap_or_else(|| self.state(source))
+                .cost()
+                .unwrap_or_else(Cost::zero);
+            // the same source may be given more than once, in which case the cheapest state wins
+            if seeded
+                .iter()
+                .any(|&(id, cost)| id == source && cost <= source_cost)
+            {
+                continue;
+            }
+            seeded.push((source, source_cost));
+            scratch.is_source[source] = true;
+            scratch.states[source] = source_state;
+            queue.insert(source, source_cost.add(heuristic(source)));
+        }
+        while !queue.is_empty() {
+            let (from, key) = queue.extract_min().unwrap();
+            if is_closed[from] {
+                // an old more expensive item for a node that has been reached via a cheaper path
+                scratch.stats.stale += 1;
+                continue;
+            }
+            if budget.exceeds_cost(&key) {
+                return Err(self.frontier(Limit::Cost, &scratch, &is_closed, heuristic));
+            }
+            let is_goal = match goal {
+                Goal::Targets(_) => is_target[from],
+                Goal::Predicate(is_goal) => is_goal(from, self.tentative(&scratch, from).unwrap()),
+            };
+            if is_goal {
+                // all other targets are going to be more expensive, since we're using priority queue
+                scratch.target = Some(from);
+                break;
+            }
+            if let Some(limit) = budget.exceeded(&scratch.stats) {
+                return Err(self.frontier(limit, &scratch, &is_closed, heuristic));
+            }
+            is_closed[from] = true;
+            scratch.stats.settled += 1;
+            let outgoing_edge_ids = self
+                .outgoing(from)
+                .filter(|&edge_id| {
+                    let to = self.edge_to(edge_id);
+                    detect_cost_decrease || (to != from && !is_closed[to])
+                })
+                .collect::<Vec<_>>();
+            if let Some(max_advanced) = budget.max_advanced {
+                if scratch.stats.advanced + outgoing_edge_ids.len() > max_advanced {
+                    // put the node back, since its edges are not going to be advanced;
+                    // the edges that would be filtered out count as well, since they are filtered in parallel
+                    is_closed[from] = false;
+                    scratch.stats.settled -= 1;
+                    return Err(self.frontier(Limit::Advanced, &scratch, &is_closed, heuristic));
+                }
+            }
+            // the nodes in the queue have all been reached
+            let from_state = self.tentative(&scratch, from).unwrap();
+            let from_estimate = heuristic(from);
+            let from_cost = from_state.cost().unwrap_or_else(Cost::zero);
+            let advanced = outgoing_edge_ids
+                .par_iter()
+                .filter(|&&edge_id| is_allowed(edge_id))
+                .map(|&edge_id| (edge_id, from_state.advance(self.props(edge_id))))
+                .collect::<Vec<_>>();
+            scratch.stats.advanced += advanced.len();
+            for (edge_id, new_state) in advanced {
+                let to = self.edge_to(edge_id);
+                let cost = match new_state.cost() {
+                    Some(cost) if allow_cost_decrease && !cost.is_nan() => cost,
+                    _ => checked_cost(&new_state, edge_id)?,
+                };
+                if detect_cost_decrease && !allow_cost_decrease && cost < from_cost {
+                    return Err(Error::CostDecrease(edge_id));
+                }
+                if is_closed[to] {
+                    continue;
+                }
+                let estimate = heuristic(to);
+                // a consistent heuristic never drops by more than the cost of an edge (up to rounding),
+                // so that nodes are settled in the order of their cost
+                if cfg!(debug_assertions)
+                    && check_heuristic
+                    && cost
+                        .add(estimate)
+                        .is_clearly_below(&from_cost.add(from_estimate))
+                {
+                    return Err(Error::InconsistentHeuristic(edge_id));
+                }
+                if let Some(old_cost) = self.tentative(&scratch, to).and_then(|state| state.cost())
+                {
+                    if old_cost <= cost {
+                        continue;
+                    }
+                }
+                // a monotone queue can't take a key below the one just extracted
+                let to_key = cost.add(estimate);
+                if Queue::IS_MONOTONE && to_key < key {
+                    return Err(Error::CostDecrease(edge_id));
+                }
+                scratch.states[to] = Some(new_state);
+                scratch.best_incoming[to] = Some(edge_id);
+                queue.insert(to, to_key);
+                // unless the queue updates the items in place, it might still have the old more expensive items
+                // for 'to', but they will be discarded when they eventually get to the front of the queue
+            }
+        }
+        Ok(scratch)
+    }
+    fn frontier(
+        &self,
+        limit: Limit,
+        scratch: &Scratch<NodeState>,
+        is_closed: &[bool],
+        heuristic: &dyn Fn(NodeId) -> NodeState::Cost,
+    ) -> Error {
+        let mut nodes = (0..self.num_nodes())
+            .filter(|&id| !is_closed[id] && scratch.is_reached(id))
+            .map(|id| {
+                let cost = self
+                    .tentative(scratch, id)
+                    .and_then(|state| state.cost())
+                    .unwrap_or_else(Cost::zero);
+                (id, cost.add(heuristic(id)))
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|&(_, key)| key);
+        let nodes = nodes.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        let path = nodes
+            .first()
+            .map(|&id| scratch.path_to(self, id))
+            .unwrap_or_default();
+        Error::BudgetExhausted(Box::new(Frontier {
+            limit,
+            nodes,
+            path,
+            stats: scratch.stats,
+        }))
+    }
+    // the tentative state of a node is the one found by the search, if any; only the sources fall back on
+    // the state stored in the graph, so that the costs left there by earlier searches don't get in the way
+    fn tentative<'a>(
+        &'a self,
+        scratch: &'a Scratch<NodeState>,
+        id: NodeId,
+    ) -> Option<&'a NodeState> {
+        match &scratch.states[id] {
+            Some(state) => Some(state),
+            None if scratch.is_source[id] => Some(self.state(id)),
+            None => None,
+        }
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> Explore<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: Topology<NodeState, EdgeProps> + Sync,
+    NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+    EdgeProps: Sync,
+{
+}
@@ -0,0 +1,553 @@
+use serde::{Deserialize, Serialize};
+
+use crate::binary::MappedGraph;
+use crate::cost::{Cost, OrderedFloat};
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, EdgeId, Graph, NodeId, ReverseTopology, Topology};
+use crate::priority_queue;
+use crate::search::Stats;
+
+// travel time along an edge as a piecewise-linear function of the departure time, given by its breakpoints;
+// the travel time is constant before the first breakpoint and after the last one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeDependentProps {
+    // (departure time, travel time), in the order of the departure times
+    pub points: Vec<(f64, f64)>,
+}
+
+impl TimeDependentProps {
+    pub fn new(points: Vec<(f64, f64)>) -> Self {
+        assert!(!points.is_empty(), "no breakpoints");
+        assert!(
+            points.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "departure times of the breakpoints are not increasing"
+        );
+        assert!(
+            points.iter().all(|&(_, travel_time)| travel_time >= 0.0),
+            "negative or NaN travel time"
+        );
+        TimeDependentProps { points }
+    }
+    pub fn constant(travel_time: f64) -> Self {
+        TimeDependentProps::new(vec![(0.0, travel_time)])
+    }
+    pub fn travel_time(&self, departure: f64) -> f64 {
+        let index = self.points.partition_point(|&(time, _)| time <= departure);
+        if index == 0 {
+            return self.points[0].1;
+        }
+        if index == self.points.len() {
+            return self.points[index - 1].1;
+        }
+        let (d0, t0) = self.points[index - 1];
+        let (d1, t1) = self.points[index];
+        t0 + (t1 - t0) * (departure - d0) / (d1 - d0)
+    }
+    pub fn arrival(&self, departure: f64) -> f64 {
+        departure + self.travel_time(departure)
+    }
+    // first in, first out: departing later never arrives earlier,
+    // i.e. the travel time never drops faster than the departure time rises
+    pub fn is_fifo(&self) -> bool {
+        self.points
+            .windows(2)
+            .all(|pair| pair[0].0 + pair[0].1 <= pair[1].0 + pair[1].1)
+    }
+    // the latest departure that arrives no later than the given time, which is well defined for FIFO edges only
+    pub fn latest_departure(&self, arrival: f64) -> f64 {
+        let &(last_departure, last_travel_time) = self.points.last().unwrap();
+        if arrival >= last_departure + last_travel_time {
+            return arrival - last_travel_time;
+        }
+        for pair in self.points.windows(2).rev() {
+            let (d0, t0) = pair[0];
+            let (d1, t1) = pair[1];
+            if arrival >= d0 + t0 {
+                // the arrival time rises at this rate along the segment, and it is above zero,
+                // since the arrival time at the end of the segment is later than the given one
+                let rate = 1.0 + (t1 - t0) / (d1 - d0);
+                return d0 + (arrival - d0 - t0) / rate;
+            }
+        }
+        arrival - self.points[0].1
+    }
+    // the travel time along this edge and then along the next one, departing from it on arrival; its breakpoints
+    // are the ones of this edge and the departures that reach the breakpoints of the next one, for FIFO edges
+    pub fn then(&self, next: &TimeDependentProps) -> TimeDependentProps {
+        let departures = self.departures().chain(
+            next.departures()
+                .map(|departure| self.latest_departure(departure)),
+        );
+        TimeDependentProps::from_departures(departures, |departure| {
+            let travel_time = self.travel_time(departure);
+            travel_time + next.travel_time(departure + travel_time)
+        })
+    }
+    // the lower of the two travel times at each departure, with breakpoints where the two cross
+    pub fn lower_envelope(&self, other: &TimeDependentProps) -> TimeDependentProps {
+        let mut departures = self
+            .departures()
+            .chain(other.departures())
+            .collect::<Vec<_>>();
+        departures.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut crossings = Vec::new();
+        for pair in departures.windows(2) {
+            let below = self.travel_time(pair[0]) - other.travel_time(pair[0]);
+            let above = self.travel_time(pair[1]) - other.travel_time(pair[1]);
+            if (below < 0.0 && above > 0.0) || (below > 0.0 && above < 0.0) {
+                crossings.push(pair[0] + (pair[1] - pair[0]) * below / (below - above));
+            }
+        }
+        TimeDependentProps::from_departures(departures.into_iter().chain(crossings), |departure| {
+            self.travel_time(departure)
+                .min(other.travel_time(departure))
+        })
+    }
+    fn departures(&self) -> impl Iterator<Item = f64> + '_ {
+        self.points.iter().map(|&(departure, _)| departure)
+    }
+    // whether the travel time is clearly lower than the other one at any departure, which is the case
+    // at one of their breakpoints if anywhere, since both are linear between them and constant outside them
+    fn is_clearly_below(&self, other: &TimeDependentProps) -> bool {
+        self.departures()
+            .chain(other.departures())
+            .any(|departure| {
+                OrderedFloat(self.travel_time(departure))
+                    .is_clearly_below(&OrderedFloat(other.travel_time(departure)))
+            })
+    }
+    // the function with breakpoints at the given departures, leaving out the ones in the middle of a straight segment,
+    // and the ones at either end with the same travel time as their neighbour, since it stays constant beyond them
+    fn from_departures(
+        departures: impl Iterator<Item = f64>,
+        travel_time: impl Fn(f64) -> f64,
+    ) -> TimeDependentProps {
+        let is_close = |a: f64, b: f64| {
+            !OrderedFloat(a).is_clearly_below(&OrderedFloat(b))
+                && !OrderedFloat(b).is_clearly_below(&OrderedFloat(a))
+        };
+        let mut departures = departures.collect::<Vec<_>>();
+        departures.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        departures.dedup();
+        let mut points: Vec<(f64, f64)> = Vec::with_capacity(departures.len());
+        for departure in departures {
+            let point = (departure, travel_time(departure));
+            if let [.., (d0, t0), (d1, t1)] = points[..] {
+                if is_close(t0 + (point.1 - t0) * (d1 - d0) / (point.0 - d0), t1) {
+                    points.pop();
+                }
+            }
+            points.push(point);
+        }
+        while points.len() > 1 && is_close(points[0].1, points[1].1) {
+            points.remove(0);
+        }
+        while points.len() > 1 && is_close(points[points.len() - 2].1, points[points.len() - 1].1) {
+            points.pop();
+        }
+        TimeDependentProps { points }
+    }
+}
+
+// EdgeProps has to implement this trait for the time-dependent searches
+pub trait TimeDependent {
+    fn time_dependent(&self) -> &TimeDependentProps;
+}
+
+impl TimeDependent for TimeDependentProps {
+    fn time_dependent(&self) -> &TimeDependentProps {
+        self
+    }
+}
+
+// path of a time-dependent search, with the times at its two ends
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedPath {
+    pub source: NodeId,
+    pub target: NodeId,
+    pub nodes: Vec<NodeId>,
+    pub edges: Vec<EdgeId>,
+    pub departure: f64,
+    pub arrival: f64,
+    pub stats: Stats,
+}
+
+// the latest departure from each node that reaches the target in time, None for the nodes that can't
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatestDepartures {
+    pub target: NodeId,
+    pub arrival: f64,
+    pub departures: Vec<Option<f64>>,
+    // the first edge of the path to the target from each node, None for the target and the nodes that can't reach it
+    pub outgoing: Vec<Option<EdgeId>>,
+    pub stats: Stats,
+}
+
+impl LatestDepartures {
+    pub fn departure(&self, id: NodeId) -> Option<f64> {
+        self.departures[id]
+    }
+    // the path from the node to the target, walking forward via the first edges
+    pub fn path<NodeState, EdgeProps>(
+        &self,
+        graph: &impl Topology<NodeState, EdgeProps>,
+        id: NodeId,
+    ) -> Option<Vec<EdgeId>> {
+        self.departures[id]?;
+        let mut node_id = id;
+        let mut path = Vec::new();
+        while let Some(edge_id) = self.outgoing[node_id] {
+            path.push(edge_id);
+            node_id = graph.edge_to(edge_id);
+        }
+        Some(path)
+    }
+}
+
+// the latest departure from each node that still reaches the target, as a function of the arrival time there:
+// the profile of a node is its travel time to the target as a function of the departure time, which inverts
+// to the latest departure for any arrival, like the travel time of an edge; None for the nodes that can't
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepartureProfiles {
+    pub target: NodeId,
+    pub profiles: Vec<Option<TimeDependentProps>>,
+    pub stats: Stats,
+}
+
+impl DepartureProfiles {
+    pub fn profile(&self, id: NodeId) -> Option<&TimeDependentProps> {
+        self.profiles[id].as_ref()
+    }
+    pub fn latest_departure(&self, id: NodeId, arrival: f64) -> Option<f64> {
+        self.profile(id)
+            .map(|profile| profile.latest_departure(arrival))
+    }
+    pub fn earliest_arrival(&self, id: NodeId, departure: f64) -> Option<f64> {
+        self.profile(id).map(|profile| profile.arrival(departure))
+    }
+}
+
+// the time-dependent searches leave the node states aside, and only use the travel time functions of the edges;
+// unlike the searches via Advance, they check that the edges they use are FIFO,
+// which is what makes it right to settle each node only once, at its earliest arrival;
+// the forward ones are the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_earliest_arrival {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync + TimeDependent,
+            {
+                pub fn check_fifo(&self) -> Result<(), Error> {
+                    match self
+                        .edge_ids()
+                        .find(|&edge_id| !self.props(edge_id).time_dependent().is_fifo())
+                    {
+                        Some(edge_id) => Err(Error::NotFifo(edge_id)),
+                        None => Ok(()),
+                    }
+                }
+                // find the path that departs from the source at the given time
+                // and arrives at any of the targets the earliest
+                pub fn earliest_arrival(
+                    &self,
+                    source: NodeId,
+                    departure: f64,
+                    targets: &[NodeId],
+                ) -> Option<TimedPath> {
+                    match self.try_earliest_arrival(source, departure, targets) {
+                        Ok(path) => Some(path),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to find earliest arrival: {}", error),
+                    }
+                }
+                pub fn try_earliest_arrival(
+                    &self,
+                    source: NodeId,
+                    departure: f64,
+                    targets: &[NodeId],
+                ) -> Result<TimedPath, Error> {
+                    self.explore_arrivals(source, departure, targets)
+                }
+            }
+        )*
+    };
+}
+
+impl_earliest_arrival!(Graph, FrozenGraph, MappedGraph);
+
+// the backward searches need the incoming edges, which Graph and FrozenGraph have
+macro_rules! impl_latest_departures {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync + TimeDependent,
+            {
+                // find the path that departs from the source the latest
+                // and still arrives at the target by the given time
+                pub fn latest_departure(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                    arrival: f64,
+                ) -> Option<TimedPath> {
+                    match self.try_latest_departure(source, target, arrival) {
+                        Ok(path) => Some(path),
+                        Err(Error::NoPath) => None,
+                        Err(error) => panic!("failed to find latest departure: {}", error),
+                    }
+                }
+                pub fn try_latest_departure(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                    arrival: f64,
+                ) -> Result<TimedPath, Error> {
+                    if !self.contains_node(source) {
+                        return Err(Error::InvalidNode(source));
+                    }
+                    let departures = self.explore_departures(target, arrival, Some(source))?;
+                    let edges = departures.path(self, source).ok_or(Error::NoPath)?;
+                    Ok(TimedPath {
+                        source,
+                        target,
+                        nodes: std::iter::once(source)
+                            .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+                            .collect(),
+                        edges,
+                        departure: departures.departures[source].unwrap(),
+                        arrival,
+                        stats: departures.stats,
+                    })
+                }
+                // find the latest departure from every node that still arrives at the target by the given time;
+                // departure_profiles finds them for all the arrival times at once
+                pub fn latest_departures(&self, target: NodeId, arrival: f64) -> LatestDepartures {
+                    match self.try_latest_departures(target, arrival) {
+                        Ok(departures) => departures,
+                        Err(error) => panic!("failed to find latest departures: {}", error),
+                    }
+                }
+                pub fn try_latest_departures(
+                    &self,
+                    target: NodeId,
+                    arrival: f64,
+                ) -> Result<LatestDepartures, Error> {
+                    self.explore_departures(target, arrival, None)
+                }
+                // find the latest departure from every node to the target as a function of the arrival time
+                pub fn departure_profiles(&self, target: NodeId) -> DepartureProfiles {
+                    match self.try_departure_profiles(target) {
+                        Ok(profiles) => profiles,
+                        Err(error) => panic!("failed to find departure profiles: {}", error),
+                    }
+                }
+                pub fn try_departure_profiles(&self, target: NodeId) -> Result<DepartureProfiles, Error> {
+                    self.explore_profiles(target)
+                }
+            }
+        )*
+    };
+}
+
+impl_latest_departures!(Graph, FrozenGraph);
+
+// the forward search itself, on anything with the topology of a graph
+trait ExploreArrivals<NodeState, EdgeProps>: Topology<NodeState, EdgeProps>
+where
+    EdgeProps: TimeDependent,
+{
+    // forward from the source along the outgoing edges, settling the nodes in the order of their earliest arrival
+    fn explore_arrivals(
+        &self,
+        source: NodeId,
+        departure: f64,
+        targets: &[NodeId],
+    ) -> Result<TimedPath, Error> {
+        for &id in std::iter::once(&source).chain(targets) {
+            if !self.contains_node(id) {
+                return Err(Error::InvalidNode(id));
+            }
+        }
+        let mut is_target = vec![false; self.num_nodes()];
+        for &id in targets {
+            is_target[id] = true;
+        }
+        let mut stats = Stats::default();
+        let mut arrivals = vec![None; self.num_nodes()];
+        let mut incoming = vec![None; self.num_nodes()];
+        let mut is_closed = vec![false; self.num_nodes()];
+        let mut queue = priority_queue::Heap::new();
+        arrivals[source] = Some(departure);
+        queue.insert(source, OrderedFloat(departure));
+        while let Some((from, OrderedFloat(time))) = queue.extract_min() {
+            if is_target[from] {
+                let mut edges = Vec::new();
+                let mut node_id = from;
+                while let Some(edge_id) = incoming[node_id] {
+                    edges.push(edge_id);
+                    node_id = self.edge_from(edge_id);
+                }
+                edges.reverse();
+                return Ok(TimedPath {
+                    source,
+                    target: from,
+                    nodes: std::iter::once(source)
+                        .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+                        .collect(),
+                    edges,
+                    departure,
+                    arrival: time,
+                    stats,
+                });
+            }
+            is_closed[from] = true;
+            stats.settled += 1;
+            for edge_id in self.outgoing(from) {
+                let to = self.edge_to(edge_id);
+                if to == from || is_closed[to] {
+                    continue;
+                }
+                let props = self.props(edge_id).time_dependent();
+                if !props.is_fifo() {
+                    return Err(Error::NotFifo(edge_id));
+                }
+                stats.advanced += 1;
+                let arrival = props.arrival(time);
+                if arrivals[to].is_some_and(|old_arrival| old_arrival <= arrival) {
+                    continue;
+                }
+                arrivals[to] = Some(arrival);
+                incoming[to] = Some(edge_id);
+                queue.insert(to, OrderedFloat(arrival));
+            }
+        }
+        Err(Error::NoPath)
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> ExploreArrivals<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: Topology<NodeState, EdgeProps>,
+    EdgeProps: TimeDependent,
+{
+}
+
+// the backward searches themselves, on anything with the topology of a graph and its incoming edges
+trait ExploreDepartures<NodeState, EdgeProps>: ReverseTopology<NodeState, EdgeProps>
+where
+    EdgeProps: TimeDependent,
+{
+    // backward from the target along the incoming edges, chaining the travel time of each edge with the profile
+    // at its end; since the fastest path depends on the time, a node can be improved at some of the departures
+    // after it has been taken from the queue, in which case it goes back in, keyed by its shortest travel time
+    fn explore_profiles(&self, target: NodeId) -> Result<DepartureProfiles, Error> {
+        if !self.contains_node(target) {
+            return Err(Error::InvalidNode(target));
+        }
+        let mut stats = Stats::default();
+        let mut profiles: Vec<Option<TimeDependentProps>> = vec![None; self.num_nodes()];
+        let mut queue = priority_queue::Heap::new();
+        profiles[target] = Some(TimeDependentProps::constant(0.0));
+        queue.insert(target, OrderedFloat(0.0));
+        while let Some((to, _)) = queue.extract_min() {
+            stats.settled += 1;
+            let profile = profiles[to].clone().unwrap();
+            for edge_id in self.incoming(to) {
+                let from = self.edge_from(edge_id);
+                if from == to {
+                    continue;
+                }
+                let props = self.props(edge_id).time_dependent();
+                if !props.is_fifo() {
+                    return Err(Error::NotFifo(edge_id));
+                }
+                stats.advanced += 1;
+                let via_edge = props.then(&profile);
+                let new_profile = match &profiles[from] {
+                    None => via_edge,
+                    Some(old_profile) if via_edge.is_clearly_below(old_profile) => {
+                        old_profile.lower_envelope(&via_edge)
+                    }
+                    Some(_) => continue,
+                };
+                let shortest = new_profile
+                    .points
+                    .iter()
+                    .map(|&(_, travel_time)| travel_time)
+                    .fold(f64::INFINITY, f64::min);
+                profiles[from] = Some(new_profile);
+                queue.insert(from, OrderedFloat(shortest));
+            }
+        }
+        Ok(DepartureProfiles {
+            target,
+            profiles,
+            stats,
+        })
+    }
+    // backward from the target along the incoming edges, settling the nodes in the order of their latest departure,
+    // the latest first, until the source is settled if there is one
+    fn explore_departures(
+        &self,
+        target: NodeId,
+        arrival: f64,
+        source: Option<NodeId>,
+    ) -> Result<LatestDepartures, Error> {
+        if !self.contains_node(target) {
+            return Err(Error::InvalidNode(target));
+        }
+        let mut stats = Stats::default();
+        let mut departures = vec![None; self.num_nodes()];
+        let mut outgoing = vec![None; self.num_nodes()];
+        let mut is_closed = vec![false; self.num_nodes()];
+        // the key is how long before the arrival a node has to be left, so that the latest departure comes first
+        let mut queue = priority_queue::Heap::new();
+        departures[target] = Some(arrival);
+        queue.insert(target, OrderedFloat(0.0));
+        while let Some((to, _)) = queue.extract_min() {
+            if Some(to) == source {
+                break;
+            }
+            is_closed[to] = true;
+            stats.settled += 1;
+            let time = departures[to].unwrap();
+            for edge_id in self.incoming(to) {
+                let from = self.edge_from(edge_id);
+                if from == to || is_closed[from] {
+                    continue;
+                }
+                let props = self.props(edge_id).time_dependent();
+                if !props.is_fifo() {
+                    return Err(Error::NotFifo(edge_id));
+                }
+                stats.advanced += 1;
+                let departure = props.latest_departure(time);
+                if departures[from].is_some_and(|old_departure| old_departure >= departure) {
+                    continue;
+                }
+                departures[from] = Some(departure);
+                outgoing[from] = Some(edge_id);
+                queue.insert(from, OrderedFloat(arrival - departure));
+            }
+        }
+        Ok(LatestDepartures {
+            target,
+            arrival,
+            departures,
+            outgoing,
+            stats,
+        })
+    }
+}
+
+impl<NodeState, EdgeProps, AnyGraph> ExploreDepartures<NodeState, EdgeProps> for AnyGraph
+where
+    AnyGraph: ReverseTopology<NodeState, EdgeProps>,
+    EdgeProps: TimeDependent,
+{
+}
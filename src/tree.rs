@@ -0,0 +1,91 @@
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, EdgeId, Graph, NodeId, Topology};
+use crate::search::{Explore, Query, Scratch};
+
+// cheapest paths from a source to all the nodes that can be reached from it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPathTree<Cost> {
+    pub source: NodeId,
+    // the last edge of the cheapest path to each node, None for the source and the nodes that can't be reached
+    pub incoming: Vec<Option<EdgeId>>,
+    // the node before the last one on the cheapest path to each node
+    pub parents: Vec<Option<NodeId>>,
+    pub costs: Vec<Option<Cost>>,
+}
+
+impl<Cost: Copy> ShortestPathTree<Cost> {
+    pub fn cost(&self, id: NodeId) -> Option<Cost> {
+        self.costs[id]
+    }
+    // the cheapest path from the source to the node, walking back via the cheapest incoming edges
+    pub fn path(&self, id: NodeId) -> Option<Vec<EdgeId>> {
+        self.costs[id]?;
+        let mut node_id = id;
+        let mut path = Vec::new();
+        while let (Some(edge_id), Some(parent)) = (self.incoming[node_id], self.parents[node_id]) {
+            path.push(edge_id);
+            node_id = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_shortest_path_tree {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find the cheapest paths from the source to all the nodes,
+                // without modifying the node states of the graph
+                pub fn shortest_path_tree(&self, source: NodeId) -> ShortestPathTree<NodeState::Cost> {
+                    match self.try_shortest_path_tree(source) {
+                        Ok(tree) => tree,
+                        Err(error) => panic!("failed to find shortest path tree: {}", error),
+                    }
+                }
+                pub fn try_shortest_path_tree(
+                    &self,
+                    source: NodeId,
+                ) -> Result<ShortestPathTree<NodeState::Cost>, Error> {
+                    // with no targets, the search goes on until all the nodes that can be reached are settled
+                    let scratch = self.explore(Query::new(source, &[]))?;
+                    Ok(self.tree(source, scratch))
+                }
+                pub(crate) fn tree(
+                    &self,
+                    source: NodeId,
+                    scratch: Scratch<NodeState>,
+                ) -> ShortestPathTree<NodeState::Cost> {
+                    let parents = scratch
+                        .best_incoming
+                        .iter()
+                        .map(|edge_id| edge_id.map(|edge_id| self.edge_from(edge_id)))
+                        .collect();
+                    // only the nodes the search has reached have a cost, whatever is stored for the others
+                    let costs = (0..self.num_nodes())
+                        .map(|id| {
+                            self.tentative(&scratch, id)
+                                .map(|state| state.cost().unwrap_or_else(Cost::zero))
+                        })
+                        .collect();
+                    ShortestPathTree {
+                        source,
+                        incoming: scratch.best_incoming,
+                        parents,
+                        costs,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_shortest_path_tree!(Graph, FrozenGraph, MappedGraph);
@@ -0,0 +1,141 @@
+use crate::binary::MappedGraph;
+use crate::cost::Cost;
+use crate::error::Error;
+use crate::frozen::FrozenGraph;
+use crate::graph::{Advance, Graph, NodeId, Topology};
+use crate::search::{Explore, Query, SearchResult};
+
+// the same on Graph, FrozenGraph and MappedGraph, like the searches of impl_search
+macro_rules! impl_k_best_paths {
+    ($($graph:ident),*) => {
+        $(
+            impl<NodeState, EdgeProps> $graph<NodeState, EdgeProps>
+            where
+                NodeState: Sync + Send + Advance<NodeState, EdgeProps>,
+                EdgeProps: Sync,
+            {
+                // find up to k cheapest paths from the source to the target that don't visit any node twice,
+                // in the order of their cost (Yen's algorithm); parallel edges make for different paths
+                pub fn k_best_paths(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                    k: usize,
+                ) -> Vec<SearchResult<NodeState, NodeState::Cost>>
+                where
+                    NodeState: Clone,
+                {
+                    match self.try_k_best_paths(source, target, k) {
+                        Ok(paths) => paths,
+                        Err(error) => panic!("failed to find k best paths: {}", error),
+                    }
+                }
+                pub fn try_k_best_paths(
+                    &self,
+                    source: NodeId,
+                    target: NodeId,
+                    k: usize,
+                ) -> Result<Vec<SearchResult<NodeState, NodeState::Cost>>, Error>
+                where
+                    NodeState: Clone,
+                {
+                    let mut paths: Vec<SearchResult<NodeState, NodeState::Cost>> = Vec::new();
+                    if k == 0 {
+                        return Ok(paths);
+                    }
+                    match self.try_search(source, &[target]) {
+                        Ok(result) => paths.push(self.trimmed(result)),
+                        Err(Error::NoPath) => return Ok(paths),
+                        Err(error) => return Err(error),
+                    }
+                    let mut candidates: Vec<SearchResult<NodeState, NodeState::Cost>> = Vec::new();
+                    while paths.len() < k {
+                        let last = paths.last().unwrap();
+                        // branch off the last path at each of its nodes but the target,
+                        // keeping the part before the branching node (root) and searching for the rest (spur)
+                        for i in 0..last.edges.len() {
+                            let spur = last.nodes[i];
+                            let root = &last.edges[..i];
+                            // the spur can't repeat a path that has already been found with the same root,
+                            // and it can't go through the root nodes, which would make a loop
+                            let blocked_edges = paths
+                                .iter()
+                                .filter(|path| path.edges.len() > i && path.edges[..i] == *root)
+                                .map(|path| path.edges[i])
+                                .collect::<Vec<_>>();
+                            let blocked_nodes = &last.nodes[..i];
+                            let is_allowed = |edge_id| {
+                                !blocked_edges.contains(&edge_id)
+                                    && !blocked_nodes.contains(&self.edge_to(edge_id))
+                            };
+                            let scratch = self.explore(Query {
+                                is_allowed: &is_allowed,
+                                ..Query::with_sources(vec![(spur, last.states[spur].clone())], &[target])
+                            })?;
+                            let spur_path = match scratch.path(self) {
+                                Some(spur_path) => spur_path,
+                                None => continue,
+                            };
+                            let mut edges = root.to_vec();
+                            edges.extend(spur_path);
+                            if candidates.iter().any(|candidate| candidate.edges == edges) {
+                                continue;
+                            }
+                            let mut states = scratch.states;
+                            for &node_id in &last.nodes[..=i] {
+                                states[node_id] = last.states[node_id].clone();
+                            }
+                            let state = states[target].clone().unwrap();
+                            candidates.push(
+                                self.trimmed(SearchResult {
+                                    source,
+                                    target,
+                                    nodes: std::iter::once(source)
+                                        .chain(edges.iter().map(|&edge_id| self.edge_to(edge_id)))
+                                        .collect(),
+                                    edges,
+                                    cost: state.cost().unwrap_or_else(Cost::zero),
+                                    state,
+                                    states,
+                                    stats: scratch.stats,
+                                }),
+                            );
+                        }
+                        // the cheapest candidate is the next best path
+                        let best = candidates
+                            .iter()
+                            .enumerate()
+                            .min_by_key(|(_, candidate)| candidate.cost)
+                            .map(|(index, _)| index);
+                        match best {
+                            Some(index) => paths.push(candidates.swap_remove(index)),
+                            None => break,
+                        }
+                    }
+                    Ok(paths)
+                }
+                // keep only the states along the path,
+                // so that each path carries the states it has been advanced through
+                fn trimmed(
+                    &self,
+                    mut result: SearchResult<NodeState, NodeState::Cost>,
+                ) -> SearchResult<NodeState, NodeState::Cost>
+                where
+                    NodeState: Clone,
+                {
+                    let mut states: Vec<Option<NodeState>> = (0..self.num_nodes()).map(|_| None).collect();
+                    for &node_id in &result.nodes {
+                        states[node_id] = result.states[node_id].take();
+                    }
+                    if states[result.nodes[0]].is_none() {
+                        states[result.nodes[0]] = Some(self.state(result.nodes[0]).clone());
+                    }
+                    result.states = states;
+                    result
+                }
+            }
+        )*
+    };
+}
+
+impl_k_best_paths!(Graph, FrozenGraph, MappedGraph);
@@ -0,0 +1,189 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph, NodeId};
+use dijkstra::Error;
+
+#[test]
+fn fewer_settled() {
+    let graph = grid3d();
+    let target = id_of(N - 1, N - 1, N - 1);
+
+    let dijkstra = graph.search(0, &[target]).unwrap();
+    let astar = graph
+        .search_astar(0, &[target], |id| distance(id, target))
+        .unwrap();
+
+    assert_eq!(astar.cost, dijkstra.cost);
+    assert_eq!(astar.cost, 3.0 * (N - 1) as f64);
+    assert_eq!(astar.nodes.len(), 3 * (N - 1) + 1);
+    assert!(astar.stats.settled * 2 < dijkstra.stats.settled);
+}
+
+#[test]
+fn zero_heuristic() {
+    let graph = grid3d();
+    let target = id_of(N - 1, 0, 0);
+
+    let dijkstra = graph.search(0, &[target]).unwrap();
+    let astar = graph
+        .search_astar(0, &[target], |_| OrderedFloat(0.0))
+        .unwrap();
+
+    assert_eq!(astar.edges, dijkstra.edges);
+    assert_eq!(astar.stats, dijkstra.stats);
+}
+
+#[test]
+fn multiple_targets() {
+    let graph = grid3d();
+    let targets = [id_of(N - 1, N - 1, N - 1), id_of(0, 3, 0)];
+    let heuristic = |id| {
+        targets
+            .iter()
+            .map(|&target| distance(id, target))
+            .min()
+            .unwrap()
+    };
+
+    let result = graph.search_astar(0, &targets, heuristic).unwrap();
+
+    assert_eq!(result.target, targets[1]);
+    assert_eq!(result.cost, 3.0);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn inconsistent() {
+    let graph = grid3d();
+    let target = id_of(N - 1, N - 1, N - 1);
+
+    // overestimates the remaining cost at the source
+    let heuristic = |id| {
+        if id == 0 {
+            OrderedFloat(100.0)
+        } else {
+            distance(id, target)
+        }
+    };
+    let error = graph.try_search_astar(0, &[target], heuristic).unwrap_err();
+
+    assert_eq!(error, Error::InconsistentHeuristic(0));
+}
+
+#[test]
+fn rounding() {
+    // points along a straight line, where the exact euclidean distance to the last one
+    // drops by the length of each segment, up to rounding
+    let points = (0..10)
+        .map(|i| (0.7 * i as f64, 0.3 * i as f64))
+        .collect::<Vec<_>>();
+    let euclidean = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+    let mut graph: Graph<State, Segment> = Graph::new();
+    for _ in &points {
+        graph.insert_node(State { cost: None });
+    }
+    for i in 0..points.len() - 1 {
+        let length = euclidean(points[i], points[i + 1]);
+        graph.insert_edge(i, i + 1, Segment { length });
+    }
+    let target = points.len() - 1;
+
+    let result = graph
+        .try_search_astar(0, &[target], |id| {
+            OrderedFloat(euclidean(points[id], points[target]))
+        })
+        .unwrap();
+
+    assert_eq!(result.nodes.len(), points.len());
+}
+
+// 3d grid with N nodes along each dimension, where each node is connected to all neighbours with unit cost
+const N: usize = 10;
+
+fn grid3d() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..N.pow(3) {
+        graph.insert_node(State { cost: None });
+    }
+    for from in 0..N.pow(3) {
+        let (i, j, k) = position_of(from);
+        if i > 0 {
+            graph.insert_edge(from, id_of(i - 1, j, k), Props { cost: 1 });
+        }
+        if i < N - 1 {
+            graph.insert_edge(from, id_of(i + 1, j, k), Props { cost: 1 });
+        }
+        if j > 0 {
+            graph.insert_edge(from, id_of(i, j - 1, k), Props { cost: 1 });
+        }
+        if j < N - 1 {
+            graph.insert_edge(from, id_of(i, j + 1, k), Props { cost: 1 });
+        }
+        if k > 0 {
+            graph.insert_edge(from, id_of(i, j, k - 1), Props { cost: 1 });
+        }
+        if k < N - 1 {
+            graph.insert_edge(from, id_of(i, j, k + 1), Props { cost: 1 });
+        }
+    }
+    graph
+}
+
+fn position_of(id: NodeId) -> (usize, usize, usize) {
+    (id % N, (id / N) % N, id / N / N)
+}
+
+fn id_of(i: usize, j: usize, k: usize) -> NodeId {
+    i + N * (j + N * k)
+}
+
+// manhattan distance, which is a consistent heuristic for unit edge costs
+fn distance(from: NodeId, to: NodeId) -> OrderedFloat {
+    let (i, j, k) = position_of(from);
+    let (x, y, z) = position_of(to);
+    OrderedFloat((i.max(x) - i.min(x) + j.max(y) - j.min(y) + k.max(z) - k.min(z)) as f64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    length: f64,
+}
+
+impl Advance<State, Segment> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Segment) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.length),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
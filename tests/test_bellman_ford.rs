@@ -0,0 +1,167 @@
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::search::SearchOptions;
+use dijkstra::Error;
+
+#[test]
+fn negative_edge() {
+    let graph = shortcut();
+
+    let result = graph.search_bellman_ford(0, &[3]).unwrap();
+
+    assert_eq!(result.edges, [1, 2, 3]);
+    assert_eq!(result.nodes, [0, 2, 1, 3]);
+    assert_eq!(result.cost, 6);
+    assert_eq!(result.states[1].as_ref().unwrap().cost, Some(2));
+}
+
+#[test]
+fn same_as_search_without_negative_costs() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..5 {
+        graph.insert_node(State { cost: None });
+    }
+    for (from, to, cost) in [
+        (0, 1, 4),
+        (0, 2, 1),
+        (2, 1, 2),
+        (1, 3, 5),
+        (2, 3, 8),
+        (3, 4, 3),
+    ] {
+        graph.insert_edge(from, to, Props { cost });
+    }
+
+    let bellman_ford = graph.search_bellman_ford(0, &[4]).unwrap();
+    let dijkstra = graph.search(0, &[4]).unwrap();
+
+    assert_eq!(bellman_ford.edges, dijkstra.edges);
+    assert_eq!(bellman_ford.cost, dijkstra.cost);
+}
+
+#[test]
+fn cheapest_target() {
+    let mut graph = shortcut();
+    let below = graph.insert_node(State { cost: None });
+    graph.insert_edge(1, below, Props { cost: -10 });
+
+    // with the costs allowed to drop, the source is not necessarily the cheapest target
+    let result = graph.search_bellman_ford(0, &[0, below]).unwrap();
+
+    assert_eq!(result.target, below);
+    assert_eq!(result.cost, -8);
+}
+
+#[test]
+fn cycle_back_to_source() {
+    let mut graph = shortcut();
+    graph.insert_edge(3, 0, Props { cost: 1 });
+
+    let result = graph.search_bellman_ford(0, &[0, 3]).unwrap();
+
+    assert_eq!(result.target, 0);
+    assert!(result.edges.is_empty());
+    assert_eq!(result.cost, 0);
+}
+
+#[test]
+fn negative_cycle() {
+    let mut graph = shortcut();
+    // 1 -> 2 -> 1 costs 1 - 2 = -1
+    graph.insert_edge(1, 2, Props { cost: 1 });
+
+    let error = graph.try_search_bellman_ford(0, &[3]).unwrap_err();
+
+    assert_eq!(error, Error::NegativeCycle(vec![2, 4]));
+}
+
+#[test]
+fn negative_self_loop() {
+    let mut graph = shortcut();
+    graph.insert_edge(3, 3, Props { cost: -1 });
+
+    let error = graph.try_search_bellman_ford(0, &[3]).unwrap_err();
+
+    assert_eq!(error, Error::NegativeCycle(vec![4]));
+}
+
+#[test]
+fn unreachable_cycle() {
+    let mut graph = shortcut();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    graph.insert_edge(a, b, Props { cost: -1 });
+    graph.insert_edge(b, a, Props { cost: -1 });
+
+    let result = graph.search_bellman_ford(0, &[3]).unwrap();
+
+    assert_eq!(result.cost, 6);
+    assert!(graph.search_bellman_ford(0, &[a]).is_none());
+}
+
+#[test]
+fn detect_cost_decrease() {
+    let graph = shortcut();
+    let options = SearchOptions {
+        detect_cost_decrease: true,
+        ..SearchOptions::default()
+    };
+
+    let error = graph.try_search_with(0, &[3], &options).unwrap_err();
+
+    assert_eq!(error, Error::CostDecrease(2));
+}
+
+#[test]
+fn undetected_cost_decrease() {
+    // the same result in debug and release builds, without detect_cost_decrease
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..3 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 1, Props { cost: 2 });
+    graph.insert_edge(1, 2, Props { cost: -1 });
+
+    let result = graph.try_search(0, &[2]).unwrap();
+
+    assert_eq!(result.edges, [0, 1]);
+    assert_eq!(result.cost, 1);
+}
+
+// 0 -3-> 1 -4-> 3
+// 0 -4-> 2 -(-2)-> 1
+fn shortcut() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..4 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 1, Props { cost: 3 });
+    graph.insert_edge(0, 2, Props { cost: 4 });
+    graph.insert_edge(2, 1, Props { cost: -2 });
+    graph.insert_edge(1, 3, Props { cost: 4 });
+    graph
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    cost: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: i64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = i64;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
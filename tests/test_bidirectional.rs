@@ -0,0 +1,175 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph, NodeId, Retreat};
+
+#[test]
+fn incoming() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+
+    let ab = graph.insert_edge(a, b, Props { cost: 1 });
+    let cb = graph.insert_edge(c, b, Props { cost: 1 });
+    let bc = graph.insert_edge(b, c, Props { cost: 1 });
+
+    assert!(graph.incoming(a).is_empty());
+    assert_eq!(graph.incoming(b), [ab, cb]);
+    assert_eq!(graph.incoming(c), [bc]);
+
+    let json = serde_json::to_string(&graph).expect("failed to serialise graph");
+    let graph: Graph<State, Props> =
+        serde_json::from_str(&json).expect("failed to deserialise graph");
+    assert!(graph.incoming(a).is_empty());
+    assert_eq!(graph.incoming(b), [ab, cb]);
+    assert_eq!(graph.incoming(c), [bc]);
+}
+
+#[test]
+fn diamond() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+    let d = graph.insert_node(State { cost: None });
+
+    graph.insert_edge(a, b, Props { cost: 1 });
+    graph.insert_edge(b, c, Props { cost: 90 });
+    let ad = graph.insert_edge(a, d, Props { cost: 10 });
+    let dc = graph.insert_edge(d, c, Props { cost: 20 });
+    graph.insert_edge(d, b, Props { cost: 1 });
+
+    let result = graph.search_bidirectional(a, &[c]).unwrap();
+
+    assert_eq!(result.edges, [ad, dc]);
+    assert_eq!(result.nodes, [a, d, c]);
+    assert_eq!(result.target, c);
+    assert_eq!(result.cost, 30.0);
+    assert_eq!(result.state.cost, Some(30.0));
+}
+
+#[test]
+fn trivial() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+    let ab = graph.insert_edge(a, b, Props { cost: 1 });
+
+    assert_eq!(graph.search_bidirectional(a, &[b]).unwrap().edges, [ab]);
+    assert!(graph
+        .search_bidirectional(a, &[a])
+        .unwrap()
+        .edges
+        .is_empty());
+    assert!(graph.search_bidirectional(a, &[c]).is_none());
+    assert!(graph.search_bidirectional(b, &[a]).is_none());
+}
+
+#[test]
+fn stored_costs() {
+    // the cost stored for the target is not remaining cost, nor is the one stored for other nodes
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: Some(0.0) });
+    let c = graph.insert_node(State { cost: Some(5.0) });
+    graph.insert_edge(a, b, Props { cost: 2 });
+    graph.insert_edge(b, c, Props { cost: 3 });
+
+    let result = graph.search_bidirectional(a, &[c]).unwrap();
+
+    assert_eq!(result.edges, [0, 1]);
+    assert_eq!(result.cost, 5.0);
+    assert_eq!(result.cost, graph.search(a, &[c]).unwrap().cost);
+}
+
+#[test]
+fn grid3d() {
+    let graph = random_grid3d();
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut unidirectional_advanced = 0;
+    let mut bidirectional_advanced = 0;
+    for _ in 0..10 {
+        let source = rng.gen_range(0, N.pow(3));
+        let targets = [rng.gen_range(0, N.pow(3))];
+
+        let unidirectional = graph.search(source, &targets).unwrap();
+        let bidirectional = graph.search_bidirectional(source, &targets).unwrap();
+
+        assert_eq!(bidirectional.cost, unidirectional.cost);
+        assert_eq!(bidirectional.nodes.first(), Some(&source));
+        assert!(targets.contains(&bidirectional.target));
+        unidirectional_advanced += unidirectional.stats.advanced;
+        bidirectional_advanced += bidirectional.stats.advanced;
+    }
+    // meeting in the middle takes roughly half as many advance calls
+    assert!(bidirectional_advanced * 5 < unidirectional_advanced * 3);
+}
+
+// 3d grid with N nodes along each dimension, where each node is connected to all neighbours
+const N: usize = 10;
+
+fn random_grid3d() -> Graph<State, Props> {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph: Graph<State, Props> = Graph::new();
+    let node_ids = (0..N.pow(3))
+        .map(|_| graph.insert_node(State { cost: None }))
+        .collect::<Vec<_>>();
+    for from in node_ids {
+        for to in neighbours(from) {
+            graph.insert_edge(from, to, Props { cost: rng.gen() });
+        }
+    }
+    graph
+}
+
+fn neighbours(id: NodeId) -> Vec<NodeId> {
+    let position_of = |id| (id % N, (id / N) % N, id / N / N);
+    let id_of = |i, j, k| i + N * (j + N * k);
+    let less = |index: usize| index.saturating_sub(1);
+    let more = |index: usize| std::cmp::min(index + 1, N - 1);
+    let (i, j, k) = position_of(id);
+    vec![
+        id_of(less(i), j, k),
+        id_of(more(i), j, k),
+        id_of(i, less(j), k),
+        id_of(i, more(j), k),
+        id_of(i, j, less(k)),
+        id_of(i, j, more(k)),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
+
+impl Retreat<State, Props> for State {
+    fn retreat(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+}
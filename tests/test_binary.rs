@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+use dijkstra::binary::MappedGraph;
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn round_trip() {
+    let graph = sample();
+    let mut bytes = Vec::new();
+
+    graph.write_binary(&mut bytes).unwrap();
+    let read: Graph<State, Props> = Graph::read_binary(&bytes[..]).unwrap();
+
+    assert_eq!(&bytes[..8], b"DIJKSTRA");
+    assert_eq!(bytes.len() % 8, 0);
+    assert_eq!(
+        serde_json::to_string(&read).unwrap(),
+        serde_json::to_string(&graph).unwrap()
+    );
+    assert_eq!(read.incoming(25), graph.incoming(25));
+}
+
+#[test]
+fn mapped() {
+    let graph = sample();
+    let path = temp_path("mapped");
+    graph
+        .write_binary(std::fs::File::create(&path).unwrap())
+        .unwrap();
+
+    let mapped: MappedGraph<State, Props> = MappedGraph::open(&path).unwrap();
+
+    assert_eq!(mapped.num_nodes(), graph.num_nodes());
+    assert_eq!(mapped.num_edges(), graph.num_edges());
+    assert!(!mapped.contains_node(3));
+    assert!(mapped.node_ids().eq(graph.node_ids()));
+    assert!(mapped.edge_ids().eq(graph.edge_ids()));
+    assert_eq!(mapped.outgoing(0), &graph.node(0).outgoing[..]);
+    for target in [23, 24, 25] {
+        let result = graph.search(0, &[target]).unwrap();
+        let mapped_result = mapped.search(0, &[target]).unwrap();
+        assert_eq!(mapped_result.edges, result.edges);
+        assert_eq!(mapped_result.cost, result.cost);
+    }
+    assert_eq!(mapped.all_pairs(), graph.all_pairs());
+    assert_eq!(
+        mapped.k_best_paths(0, 25, 3)[2].edges,
+        graph.k_best_paths(0, 25, 3)[2].edges
+    );
+    // a file can't be removed while it is mapped on windows
+    drop(mapped);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn invalid() {
+    let mut bytes = Vec::new();
+    sample().write_binary(&mut bytes).unwrap();
+    let read = |bytes: &[u8]| Graph::<State, Props>::read_binary(bytes).unwrap_err();
+
+    assert_eq!(
+        read(b"{\"nodes\": []}"),
+        Error::InvalidFormat("not a binary graph".to_string())
+    );
+    let mut version = bytes.clone();
+    version[8] = 2;
+    assert_eq!(
+        read(&version),
+        Error::InvalidFormat("unsupported version 2".to_string())
+    );
+    assert_eq!(
+        read(&bytes[..bytes.len() - 8]),
+        Error::InvalidFormat("truncated".to_string())
+    );
+    // the target of the first edge, after the header, the 27 offsets, the outgoing ids and the 100 sources
+    let num_outgoing = bytes[32] as usize;
+    let mut target = bytes.clone();
+    target[48 + 27 * 8 + num_outgoing * 8 + 100 * 8] = 99;
+    assert_eq!(
+        read(&target),
+        Error::InvalidFormat("edges[0].to: invalid node id 99".to_string())
+    );
+    assert!(matches!(
+        MappedGraph::<State, Props>::open(temp_path("missing")),
+        Err(Error::Io(_))
+    ));
+}
+
+#[test]
+fn invalid_mapped() {
+    let mut bytes = Vec::new();
+    sample().write_binary(&mut bytes).unwrap();
+    let open = |bytes: &[u8]| {
+        let path = temp_path("invalid");
+        std::fs::write(&path, bytes).unwrap();
+        // the topology is only checked on request, which takes time linear in the number of edges
+        let is_opened = MappedGraph::<State, Props>::open(&path).is_ok();
+        let error = MappedGraph::<State, Props>::open_checked(&path)
+            .err()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(is_opened);
+        error
+    };
+
+    // the last offset, which has to be the number of outgoing edge ids
+    let mut offsets = bytes.clone();
+    offsets[48 + 26 * 8] += 1;
+    assert_eq!(
+        open(&offsets),
+        Error::InvalidFormat("offsets out of order".to_string())
+    );
+    let num_outgoing = bytes[32] as usize;
+    let mut target = bytes.clone();
+    target[48 + 27 * 8 + num_outgoing * 8 + 100 * 8] = 99;
+    assert_eq!(
+        open(&target),
+        Error::InvalidFormat("edges[0].to: invalid node id 99".to_string())
+    );
+    // the first outgoing edge id of node 0
+    let mut outgoing = bytes.clone();
+    outgoing[48 + 27 * 8] = 200;
+    assert_eq!(
+        open(&outgoing),
+        Error::InvalidFormat("nodes[0].outgoing[0]: invalid edge id 200".to_string())
+    );
+}
+
+// the graph of test_serde, with a node removed along with its edges
+fn sample() -> Graph<State, Props> {
+    let file = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/graph.json");
+    let json = std::fs::read_to_string(file).expect("failed to read from json file");
+    let mut graph: Graph<State, Props> =
+        serde_json::from_str(&json).expect("failed to deserialise graph");
+    graph.remove_node(3);
+    graph
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("dijkstra-{}-{}.bin", name, std::process::id()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct State {
+    name: char,
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Props {
+    cost: f64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            name: self.name,
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
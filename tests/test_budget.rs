@@ -0,0 +1,168 @@
+use std::time::{Duration, Instant};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::search::{Budget, Limit, SearchOptions};
+use dijkstra::Error;
+
+#[test]
+fn within_budget() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            max_cost: Some(OrderedFloat(4.0)),
+            max_settled: Some(4),
+            max_advanced: Some(4),
+            deadline: Some(Instant::now() + Duration::from_secs(60)),
+        },
+        ..SearchOptions::default()
+    };
+
+    let result = graph.search_with(0, &[4], &options).unwrap();
+
+    assert_eq!(result.edges, [0, 1, 2, 3]);
+}
+
+#[test]
+fn max_cost() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            max_cost: Some(OrderedFloat(2.5)),
+            ..Budget::default()
+        },
+        ..SearchOptions::default()
+    };
+
+    match graph.try_search_with(0, &[4], &options) {
+        Err(Error::BudgetExhausted(frontier)) => {
+            assert_eq!(frontier.limit, Limit::Cost);
+            assert_eq!(frontier.nodes, [3]);
+            assert_eq!(frontier.path, [0, 1, 2]);
+            assert_eq!(frontier.stats.settled, 3);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn target_above_max_cost() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            max_cost: Some(OrderedFloat(2.5)),
+            ..Budget::default()
+        },
+        ..SearchOptions::default()
+    };
+
+    match graph.try_search_with(0, &[3], &options) {
+        Err(Error::BudgetExhausted(frontier)) => {
+            assert_eq!(frontier.limit, Limit::Cost);
+            assert_eq!(frontier.nodes, [3]);
+            assert_eq!(frontier.path, [0, 1, 2]);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn max_settled() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            max_settled: Some(2),
+            ..Budget::default()
+        },
+        ..SearchOptions::default()
+    };
+
+    match graph.try_search_with(0, &[4], &options) {
+        Err(Error::BudgetExhausted(frontier)) => {
+            assert_eq!(frontier.limit, Limit::Settled);
+            assert_eq!(frontier.nodes, [2]);
+            assert_eq!(frontier.path, [0, 1]);
+            assert_eq!(frontier.stats.settled, 2);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn max_advanced() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            max_advanced: Some(1),
+            ..Budget::default()
+        },
+        ..SearchOptions::default()
+    };
+
+    match graph.try_search_with(0, &[4], &options) {
+        Err(Error::BudgetExhausted(frontier)) => {
+            assert_eq!(frontier.limit, Limit::Advanced);
+            assert_eq!(frontier.nodes, [1]);
+            assert_eq!(frontier.stats.advanced, 1);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn deadline() {
+    let graph = chain();
+    let options = SearchOptions {
+        budget: Budget {
+            deadline: Some(Instant::now()),
+            ..Budget::default()
+        },
+        ..SearchOptions::default()
+    };
+
+    match graph.try_search_with(0, &[4], &options) {
+        Err(Error::BudgetExhausted(frontier)) => {
+            assert_eq!(frontier.limit, Limit::Deadline);
+            assert_eq!(frontier.nodes, [0]);
+            assert!(frontier.path.is_empty());
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+// 0 -> 1 -> 2 -> 3 -> 4 with unit costs
+fn chain() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..5 {
+        graph.insert_node(State { cost: None });
+    }
+    for from in 0..4 {
+        graph.insert_edge(from, from + 1, Props { cost: 1 });
+    }
+    graph
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use dijkstra::compact::CompactGraph;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn round_trip() {
+    let json = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/graph.json"),
+    )
+    .unwrap();
+    let mut graph: Graph<State, Props> = serde_json::from_str(&json).unwrap();
+    graph.remove_node(3);
+    graph.remove_edge(0);
+    let mut compact = Vec::new();
+
+    graph.write_compact_json(&mut compact).unwrap();
+    let read: Graph<State, Props> = Graph::read_compact_json(&compact[..]).unwrap();
+
+    assert!(compact.len() * 2 < json.len());
+    assert_eq!(
+        serde_json::to_string(&read).unwrap(),
+        serde_json::to_string(&graph).unwrap()
+    );
+    assert_eq!(read.incoming(25), graph.incoming(25));
+}
+
+#[test]
+fn schema() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..3 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 1, Props { cost: 1.0 });
+    graph.insert_edge(1, 2, Props { cost: 2.0 });
+    graph.insert_edge(2, 0, Props { cost: 3.0 });
+    graph.remove_edge(1);
+
+    let json = serde_json::to_string(&graph.to_compact()).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"states":[{"cost":null},{"cost":null},{"cost":null}],"edges":[[0,1],[1,2],[2,0]],"#
+            .to_string()
+            + r#""props":[{"cost":1.0},{"cost":2.0},{"cost":3.0}],"removed_edges":[1]}"#
+    );
+}
+
+#[test]
+fn invalid_compact() {
+    let read = |json: &str| Graph::<State, Props>::read_compact_json(json.as_bytes()).unwrap_err();
+    let states = r#""states": [{"cost": null}, {"cost": null}]"#;
+
+    assert_eq!(
+        read(&format!(
+            r#"{{{}, "edges": [[0, 1], [1, 7]], "props": [{{"cost": 1}}, {{"cost": 1}}]}}"#,
+            states
+        )),
+        Error::InvalidFormat("edges[1][1]: invalid node id 7".to_string())
+    );
+    assert_eq!(
+        read(&format!(
+            r#"{{{}, "edges": [[0, 1]], "props": []}}"#,
+            states
+        )),
+        Error::InvalidFormat("props: 0 props for 1 edges".to_string())
+    );
+    assert_eq!(
+        read(&format!(
+            r#"{{{}, "edges": [[0, 1]], "props": [{{"cost": 1}}], "removed_nodes": [1]}}"#,
+            states
+        )),
+        Error::InvalidFormat("edges[0][1]: node 1 is removed".to_string())
+    );
+    assert_eq!(
+        read(&format!(
+            r#"{{{}, "edges": [], "props": [], "removed_edges": [0]}}"#,
+            states
+        )),
+        Error::InvalidFormat("removed_edges[0]: invalid edge id 0".to_string())
+    );
+    match read(r#"{"states": [], "edges": [[0]], "props": []}"#) {
+        Error::InvalidFormat(message) => assert!(message.contains("line 1 column"), "{}", message),
+        error => panic!("unexpected error {:?}", error),
+    }
+    let compact = CompactGraph::<State, Props> {
+        states: vec![State { cost: None }],
+        edges: vec![(0, 0)],
+        props: vec![Props { cost: 1.0 }],
+        removed_nodes: vec![0, 0],
+        removed_edges: vec![0],
+    };
+    assert_eq!(
+        Graph::try_from(compact).unwrap_err(),
+        Error::InvalidFormat("removed_nodes[1]: node 0 is listed twice".to_string())
+    );
+}
+
+#[test]
+fn invalid_full() {
+    let read = |json: &str| {
+        serde_json::from_str::<Graph<State, Props>>(json)
+            .unwrap_err()
+            .to_string()
+    };
+    let states = r#""states": [{"cost": null}, {"cost": null}], "props": [{"cost": 1}]"#;
+
+    let id = read(&format!(
+        r#"{{"nodes": [{{"id": 0, "outgoing": [0]}}, {{"id": 0, "outgoing": []}}], "edges": [{{"id": 0, "from": 0, "to": 1}}], {}}}"#,
+        states
+    ));
+    let from = read(&format!(
+        r#"{{"nodes": [{{"id": 0, "outgoing": []}}, {{"id": 1, "outgoing": [0]}}], "edges": [{{"id": 0, "from": 0, "to": 1}}], {}}}"#,
+        states
+    ));
+    let missing = read(&format!(
+        r#"{{"nodes": [{{"id": 0, "outgoing": []}}, {{"id": 1, "outgoing": []}}], "edges": [{{"id": 0, "from": 0, "to": 1}}], {}}}"#,
+        states
+    ));
+
+    assert!(
+        id.starts_with("invalid format: nodes[1].id: expected 1"),
+        "{}",
+        id
+    );
+    assert!(
+        from.starts_with("invalid format: nodes[1].outgoing[0]: edge 0 is from node 0"),
+        "{}",
+        from
+    );
+    assert!(
+        missing.starts_with("invalid format: nodes[0].outgoing: edge 0 is missing"),
+        "{}",
+        missing
+    );
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Props {
+    cost: f64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = dijkstra::cost::OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<Self::Cost> {
+        self.cost.map(Into::into)
+    }
+}
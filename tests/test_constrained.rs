@@ -0,0 +1,110 @@
+use dijkstra::graph::{Advance, Dominate, Graph, TryAdvance};
+use dijkstra::Error;
+
+#[test]
+fn keeps_labels_with_more_resources() {
+    let graph = chargers(8);
+
+    let result = graph.constrained_path(0, 3).unwrap();
+
+    // the cheapest way to 2 runs the battery too low to get on to 3
+    assert_eq!(result.edges, [3, 4]);
+    assert_eq!(result.nodes, [0, 2, 3]);
+    assert_eq!(result.cost, 8);
+    assert_eq!(result.state.battery, 2);
+    assert_eq!(result.states[2].as_ref().unwrap().cost, 4);
+}
+
+#[test]
+fn without_limits() {
+    let graph = chargers(100);
+
+    let result = graph.constrained_path(0, 3).unwrap();
+
+    assert_eq!(result.edges, [1, 2]);
+    assert_eq!(result.cost, 4);
+}
+
+#[test]
+fn infeasible() {
+    let graph = chargers(5);
+
+    assert!(graph.constrained_path(0, 3).is_none());
+    assert_eq!(graph.try_constrained_path(0, 3).unwrap_err(), Error::NoPath);
+    assert_eq!(
+        graph.try_constrained_path(9, 3).unwrap_err(),
+        Error::InvalidNode(9)
+    );
+}
+
+#[test]
+fn trivial() {
+    let graph = chargers(0);
+
+    let result = graph.constrained_path(3, 3).unwrap();
+
+    assert!(result.edges.is_empty());
+    assert_eq!(result.cost, 0);
+}
+
+// the edges with their cost and how much of the battery they drain:
+// 0: 0 -> 3 (5, 10)
+// 1: 0 -> 1 (2, 6)
+// 2: 1 -> 3 (2, 6)
+// 3: 0 -> 2 (4, 1)
+// 4: 2 -> 3 (4, 5)
+// 5: 1 -> 2 (1, 1)
+fn chargers(battery: u32) -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    graph.insert_node(State { cost: 0, battery });
+    for _ in 1..4 {
+        graph.insert_node(State::default());
+    }
+    graph.insert_edge(0, 3, Props { cost: 5, drain: 10 });
+    graph.insert_edge(0, 1, Props { cost: 2, drain: 6 });
+    graph.insert_edge(1, 3, Props { cost: 2, drain: 6 });
+    graph.insert_edge(0, 2, Props { cost: 4, drain: 1 });
+    graph.insert_edge(2, 3, Props { cost: 4, drain: 5 });
+    graph.insert_edge(1, 2, Props { cost: 1, drain: 1 });
+    graph
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    cost: u32,
+    battery: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: u32,
+    drain: u32,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = u32;
+    fn advance(&self, edge_props: &Props) -> State {
+        self.try_advance(edge_props).expect("battery drained")
+    }
+    fn update(&mut self, node_state: State) {
+        *self = node_state;
+    }
+    fn cost(&self) -> Option<u32> {
+        Some(self.cost)
+    }
+}
+
+impl Dominate<State, Props> for State {
+    fn dominates(&self, other: &State) -> bool {
+        self.cost <= other.cost && self.battery >= other.battery
+    }
+}
+
+impl TryAdvance<State, Props> for State {
+    fn try_advance(&self, edge_props: &Props) -> Option<State> {
+        Some(State {
+            cost: self.cost + edge_props.cost,
+            battery: self.battery.checked_sub(edge_props.drain)?,
+        })
+    }
+}
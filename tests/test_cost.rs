@@ -0,0 +1,216 @@
+use dijkstra::cost::{Cost, OrderedFloat};
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::priority_queue::{BucketQueue, RadixHeap};
+use dijkstra::search::SearchOptions;
+use dijkstra::Error;
+
+#[test]
+fn integer() {
+    let graph = diamond();
+
+    let result = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(result.edges, [2, 3]);
+    assert_eq!(result.cost, (2, 30));
+}
+
+#[test]
+fn lexicographic() {
+    let mut graph = diamond();
+    // a direct edge is more expensive, but has fewer hops
+    graph.insert_edge(0, 3, Props { cost: 100 });
+
+    let result = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(result.edges, [4]);
+    assert_eq!(result.cost, (1, 100));
+}
+
+#[test]
+fn monotone_queues() {
+    let mut graph: Graph<Distance, Props> = Graph::new();
+    let a = graph.insert_node(Distance { cost: Some(0) });
+    let b = graph.insert_node(Distance { cost: None });
+    let c = graph.insert_node(Distance { cost: None });
+    graph.insert_edge(a, b, Props { cost: 5 });
+    graph.insert_edge(b, c, Props { cost: 5 });
+    graph.insert_edge(a, c, Props { cost: 20 });
+    let options = SearchOptions::default();
+
+    let radix = graph
+        .try_search_with_queue::<RadixHeap<u32>>(a, &[c], &options)
+        .unwrap();
+    let bucket = graph
+        .try_search_with_queue::<BucketQueue<u32>>(a, &[c], &options)
+        .unwrap();
+
+    assert_eq!(radix.cost, 10);
+    assert_eq!(bucket.cost, 10);
+    assert_eq!(radix.edges, bucket.edges);
+}
+
+#[test]
+fn monotone_queues_cost_decrease() {
+    let mut graph: Graph<Refund, Props> = Graph::new();
+    let a = graph.insert_node(Refund { cost: Some(20) });
+    let b = graph.insert_node(Refund { cost: None });
+    let edge = graph.insert_edge(a, b, Props { cost: 5 });
+    let options = SearchOptions::default();
+
+    // the cost drops from 20 to 15, below the key just taken from the queue
+    assert_eq!(
+        graph
+            .try_search_with_queue::<RadixHeap<u32>>(a, &[b], &options)
+            .unwrap_err(),
+        Error::CostDecrease(edge)
+    );
+    assert_eq!(
+        graph
+            .try_search_with_queue::<BucketQueue<u32>>(a, &[b], &options)
+            .unwrap_err(),
+        Error::CostDecrease(edge)
+    );
+}
+
+#[test]
+fn ordered_float() {
+    let mut costs = [
+        OrderedFloat(2.0),
+        OrderedFloat(f64::NAN),
+        OrderedFloat(-1.0),
+        OrderedFloat(0.5),
+    ];
+    costs.sort();
+
+    assert_eq!(costs[..3], [-1.0, 0.5, 2.0]);
+    assert!(costs[3].is_nan());
+    assert_eq!(OrderedFloat(-0.0), OrderedFloat(0.0));
+    assert_eq!(OrderedFloat(0.5).add(OrderedFloat(0.25)), 0.75);
+    assert_eq!(OrderedFloat(0.5).sub(OrderedFloat(0.75)), -0.25);
+    assert!(!OrderedFloat(0.1 + 0.2).is_clearly_below(&OrderedFloat(0.3)));
+    assert!(!OrderedFloat(0.3).is_clearly_below(&OrderedFloat(0.1 + 0.2)));
+    assert!(OrderedFloat(0.2).is_clearly_below(&OrderedFloat(0.3)));
+    assert!((1, OrderedFloat(0.3)).is_clearly_below(&(2, OrderedFloat(0.1 + 0.2))));
+    assert!(!(1, OrderedFloat(0.3)).is_clearly_below(&(1, OrderedFloat(0.1 + 0.2))));
+    assert!(1u8.is_clearly_below(&2));
+    assert_eq!(<(u8, OrderedFloat)>::zero(), (0, OrderedFloat(0.0)));
+    assert!((1, OrderedFloat(f64::NAN)).is_nan());
+}
+
+#[test]
+fn nan() {
+    let mut graph: Graph<Float, Props> = Graph::new();
+    graph.insert_node(Float {
+        cost: Some(f64::INFINITY),
+    });
+    graph.insert_node(Float { cost: None });
+    // infinity minus infinity
+    graph.insert_edge(0, 1, Props { cost: 0 });
+
+    let error = graph.try_search(0, &[1]).unwrap_err();
+
+    assert_eq!(error, Error::NaNCost(0));
+}
+
+// a -10-> b -25-> d
+// a -10-> c -20-> d
+fn diamond() -> Graph<Hops, Props> {
+    let mut graph: Graph<Hops, Props> = Graph::new();
+    let a = graph.insert_node(Hops { cost: Some((0, 0)) });
+    let b = graph.insert_node(Hops { cost: None });
+    let c = graph.insert_node(Hops { cost: None });
+    let d = graph.insert_node(Hops { cost: None });
+    graph.insert_edge(a, b, Props { cost: 10 });
+    graph.insert_edge(b, d, Props { cost: 25 });
+    graph.insert_edge(a, c, Props { cost: 10 });
+    graph.insert_edge(c, d, Props { cost: 20 });
+    graph
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: u32,
+}
+
+// the number of edges and the distance so far
+#[derive(Debug, Clone)]
+struct Hops {
+    cost: Option<(u32, u32)>,
+}
+
+impl Advance<Hops, Props> for Hops {
+    type Cost = (u32, u32);
+    fn advance(&self, edge_props: &Props) -> Hops {
+        let (hops, distance) = self.cost.unwrap_or((0, 0));
+        Hops {
+            cost: Some((hops + 1, distance + edge_props.cost)),
+        }
+    }
+    fn update(&mut self, node_state: Hops) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<(u32, u32)> {
+        self.cost
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Distance {
+    cost: Option<u32>,
+}
+
+impl Advance<Distance, Props> for Distance {
+    type Cost = u32;
+    fn advance(&self, edge_props: &Props) -> Distance {
+        Distance {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: Distance) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<u32> {
+        self.cost
+    }
+}
+
+// the distance, with a refund of up to 10 at each node
+#[derive(Debug, Clone)]
+struct Refund {
+    cost: Option<u32>,
+}
+
+impl Advance<Refund, Props> for Refund {
+    type Cost = u32;
+    fn advance(&self, edge_props: &Props) -> Refund {
+        Refund {
+            cost: Some(self.cost.unwrap_or(0).saturating_sub(10) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: Refund) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<u32> {
+        self.cost
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Float {
+    cost: Option<f64>,
+}
+
+impl Advance<Float, Props> for Float {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> Float {
+        Float {
+            cost: Some(self.cost.unwrap_or(0.0) - f64::INFINITY + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: Float) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
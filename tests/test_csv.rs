@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use dijkstra::csv::{CSV, TSV};
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+const EDGES: &str = "from,to,weight,name\n0,1,4,\"main, north\"\n1,2,3,\n0,2,9,\"the \"\"long\"\" way\"\n\n2,3,1,last\n";
+
+#[test]
+fn read() {
+    let graph: Graph<State, Props> = Graph::read_csv(EDGES.as_bytes(), CSV).unwrap();
+
+    assert_eq!(graph.num_nodes(), 4);
+    assert_eq!(graph.num_edges(), 4);
+    assert_eq!((graph.edge(2).from, graph.edge(2).to), (0, 2));
+    assert_eq!(graph.props(0).name, Some("main, north".to_string()));
+    assert_eq!(graph.props(1).name, None);
+    assert_eq!(graph.props(2).name, Some("the \"long\" way".to_string()));
+    assert_eq!(graph.search(0, &[3]).unwrap().cost, 8);
+}
+
+#[test]
+fn read_tsv_with_nodes() {
+    // the columns can come in any order, and the nodes that aren't listed get the default state
+    let edges = "weight\tto\tfrom\n4\t1\t0\n3\t2\t1\n";
+    let nodes = "id\tcost\n0\t10\n4\t\n";
+
+    let graph: Graph<State, Props> =
+        Graph::read_csv_with_nodes(edges.as_bytes(), nodes.as_bytes(), TSV).unwrap();
+
+    assert_eq!(graph.num_nodes(), 5);
+    assert_eq!(graph.state(0).cost, Some(10));
+    assert_eq!(graph.state(1).cost, None);
+    assert_eq!(graph.search(0, &[2]).unwrap().cost, 17);
+}
+
+#[test]
+fn write() {
+    let mut graph: Graph<State, Props> = Graph::read_csv(EDGES.as_bytes(), CSV).unwrap();
+    graph.remove_edge(1);
+    let mut edges = Vec::new();
+    let mut nodes = Vec::new();
+    let mut path = Vec::new();
+
+    graph.write_csv_edges(&mut edges, CSV).unwrap();
+    graph.write_csv_nodes(&mut nodes, TSV).unwrap();
+    let result = graph.search(0, &[3]).unwrap();
+    graph.write_csv_path(&result, &mut path, CSV).unwrap();
+
+    let edges = String::from_utf8(edges).unwrap();
+    assert_eq!(
+        edges,
+        "from,to,weight,name\n0,1,4,\"main, north\"\n0,2,9,\"the \"\"long\"\" way\"\n2,3,1,last\n"
+    );
+    assert_eq!(
+        String::from_utf8(nodes).unwrap(),
+        "id\tcost\n0\t\n1\t\n2\t\n3\t\n"
+    );
+    assert_eq!(
+        String::from_utf8(path).unwrap(),
+        "node,edge,cost\n0,,\n2,2,9\n3,3,10\n"
+    );
+    let read: Graph<State, Props> = Graph::read_csv(edges.as_bytes(), CSV).unwrap();
+    assert_eq!(read.num_edges(), 3);
+    assert_eq!(read.props(1), graph.props(2));
+}
+
+#[test]
+fn field_kinds() {
+    // the fields are written in the order of the struct, and read back as they were written
+    let mut graph: Graph<State, Road> = Graph::new();
+    let a = graph.insert_node(State::default());
+    let b = graph.insert_node(State::default());
+    let road = Road {
+        surface: Surface::Gravel,
+        length: Metres(2.5),
+        toll: None,
+        lanes: Some(2),
+    };
+    graph.insert_edge(a, b, road.clone());
+    let mut edges = Vec::new();
+
+    graph.write_csv_edges(&mut edges, CSV).unwrap();
+
+    let edges = String::from_utf8(edges).unwrap();
+    assert_eq!(
+        edges,
+        "from,to,surface,length,toll,lanes\n0,1,Gravel,2.5,,2\n"
+    );
+    let read: Graph<State, Road> = Graph::read_csv(edges.as_bytes(), CSV).unwrap();
+    assert_eq!(read.props(0), &road);
+}
+
+#[test]
+fn nested() {
+    // a cell holds a single value, so a list can't be written as a field and read back
+    let mut graph: Graph<State, Stops> = Graph::new();
+    let a = graph.insert_node(State::default());
+    let b = graph.insert_node(State::default());
+    graph.insert_edge(a, b, Stops { stops: vec![3, 4] });
+
+    let error = graph.write_csv_edges(Vec::new(), CSV).unwrap_err();
+
+    assert_eq!(
+        error,
+        Error::InvalidFormat("stops: nested values don't fit in a field".to_string())
+    );
+}
+
+#[test]
+fn invalid() {
+    let read = |text: &str| Graph::<State, Props>::read_csv(text.as_bytes(), CSV).unwrap_err();
+
+    assert_eq!(read(""), Error::InvalidFormat("no header".to_string()));
+    assert_eq!(
+        read("from,weight\n0,1\n"),
+        Error::InvalidFormat("line 1: no to column".to_string())
+    );
+    assert_eq!(
+        read("from,to,weight\n0,1,4\n1,2\n"),
+        Error::InvalidFormat("line 3: 2 fields for 3 columns".to_string())
+    );
+    assert_eq!(
+        read("from,to,weight\n0,x,4\n"),
+        Error::InvalidFormat("line 2: invalid node id x".to_string())
+    );
+    assert_eq!(
+        read("from,to,weight\n0,1,four\n"),
+        Error::InvalidFormat("line 2: four: invalid digit found in string".to_string())
+    );
+    assert_eq!(
+        read("from,to\n0,1\n"),
+        Error::InvalidFormat("line 2: missing field `weight`".to_string())
+    );
+    assert_eq!(
+        read("from,to,weight,name\n0,1,4,\"open\n"),
+        Error::InvalidFormat("line 2: unterminated quote".to_string())
+    );
+    assert_eq!(
+        Graph::<State, Props>::read_csv_with_nodes(
+            "from,to,weight\n0,1,4\n".as_bytes(),
+            "id,cost\n1,2\n1,3\n".as_bytes(),
+            CSV
+        )
+        .unwrap_err(),
+        Error::InvalidFormat("line 3: node 1 is listed twice".to_string())
+    );
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct State {
+    cost: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Props {
+    weight: i64,
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Road {
+    surface: Surface,
+    length: Metres,
+    toll: Option<f64>,
+    lanes: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Surface {
+    Asphalt,
+    Gravel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Metres(f64);
+
+#[derive(Debug, Clone, Serialize)]
+struct Stops {
+    stops: Vec<u8>,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = i64;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.weight),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
+
+impl Advance<State, Stops> for State {
+    type Cost = i64;
+    fn advance(&self, edge_props: &Stops) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.stops.len() as i64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
+
+impl Advance<State, Road> for State {
+    type Cost = i64;
+    fn advance(&self, _edge_props: &Road) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + 1),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::BufReader;
+
+use dijkstra::dimacs::{read_dimacs_coordinates, write_dimacs_coordinates};
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn read() {
+    let graph = sample();
+
+    assert_eq!(graph.num_nodes(), 6);
+    assert_eq!(graph.num_edges(), 13);
+    assert_eq!((graph.edge(0).from, graph.edge(0).to), (0, 1));
+    assert_eq!(graph.props(10).weight, 15);
+    assert_eq!(graph.node(1).outgoing, [1, 2, 10]);
+}
+
+#[test]
+fn astar_with_coordinates() {
+    let graph = sample();
+    let coordinates = read_dimacs_coordinates(open("tests/sample.co")).unwrap();
+    let (x, y) = coordinates[5];
+    // the straight line distance to the target, rounded down, which never overestimates the weights
+    let heuristic = |id: usize| {
+        let (dx, dy) = (coordinates[id].0 - x, coordinates[id].1 - y);
+        ((dx * dx + dy * dy) as f64).sqrt() as i64
+    };
+
+    let result = graph.search_astar(0, &[5], heuristic).unwrap();
+
+    assert_eq!(coordinates[1], (10, 0));
+    assert_eq!(result.nodes, [0, 3, 4, 5]);
+    assert_eq!(result.cost, 31);
+    assert_eq!(result.edges, graph.search(0, &[5]).unwrap().edges);
+}
+
+#[test]
+fn write() {
+    let mut graph = sample();
+    graph.remove_edge(12);
+    let mut gr = Vec::new();
+    let mut co = Vec::new();
+
+    graph.write_dimacs(&mut gr, |props| props.weight).unwrap();
+    write_dimacs_coordinates(&mut co, &[(0, 0), (10, 0)]).unwrap();
+
+    let gr = String::from_utf8(gr).unwrap();
+    assert!(gr.starts_with("p sp 6 12\na 1 2 10\na 2 1 10\n"));
+    assert!(gr.ends_with("a 2 5 15\na 3 6 10\n"));
+    let read = Graph::read_dimacs(
+        gr.as_bytes(),
+        |_| State::default(),
+        |weight| Props { weight },
+    )
+    .unwrap();
+    assert_eq!(read.num_edges(), 12);
+    assert_eq!(read.search(0, &[5]).unwrap().cost, 31);
+    assert_eq!(
+        String::from_utf8(co).unwrap(),
+        "p aux sp co 2\nv 1 0 0\nv 2 10 0\n"
+    );
+}
+
+#[test]
+fn invalid() {
+    let read = |text: &str| {
+        Graph::read_dimacs(
+            text.as_bytes(),
+            |_| State::default(),
+            |weight| Props { weight },
+        )
+        .unwrap_err()
+    };
+
+    assert_eq!(
+        read("p sp 2 1\na 1 3 5\n"),
+        Error::InvalidFormat("line 2: invalid node id 3".to_string())
+    );
+    assert_eq!(
+        read("c no problem line\na 1 2 5\n"),
+        Error::InvalidFormat("line 2: unexpected a 1 2 5".to_string())
+    );
+    assert_eq!(
+        read("p sp 2 2\na 1 2 five\n"),
+        Error::InvalidFormat("line 2: invalid number five".to_string())
+    );
+    assert_eq!(
+        read("p sp 2 2\na 1 2 5\n"),
+        Error::InvalidFormat("1 arcs for 2 in the problem line".to_string())
+    );
+    assert_eq!(
+        read(""),
+        Error::InvalidFormat("no problem line".to_string())
+    );
+    assert_eq!(
+        read_dimacs_coordinates("p aux sp co 2\nv 2 0 0\n".as_bytes()).unwrap_err(),
+        Error::InvalidFormat("no coordinates for node 1".to_string())
+    );
+    assert_eq!(
+        read_dimacs_coordinates("p aux sp co 2\nv 1 0 0\nv 2 1 1\nv 1 2 2\n".as_bytes())
+            .unwrap_err(),
+        Error::InvalidFormat("line 4: node 1 is listed twice".to_string())
+    );
+}
+
+fn sample() -> Graph<State, Props> {
+    Graph::read_dimacs(
+        open("tests/sample.gr"),
+        |_| State::default(),
+        |weight| Props { weight },
+    )
+    .unwrap()
+}
+
+fn open(path: &str) -> BufReader<std::fs::File> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    BufReader::new(std::fs::File::open(path).unwrap())
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    cost: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    weight: i64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = i64;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.weight),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
@@ -0,0 +1,106 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn invalid_node() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+
+    assert_eq!(
+        graph.try_insert_edge(a, 1, Props { cost: Some(1.0) }),
+        Err(Error::InvalidNode(1))
+    );
+    assert_eq!(
+        graph.try_insert_edge(2, a, Props { cost: Some(1.0) }),
+        Err(Error::InvalidNode(2))
+    );
+    assert_eq!(graph.num_edges(), 0);
+    assert!(graph.node(a).outgoing.is_empty());
+
+    assert_eq!(graph.try_best_path(a, &[3]), Err(Error::InvalidNode(3)));
+    assert_eq!(
+        graph.try_search(4, &[a]).unwrap_err(),
+        Error::InvalidNode(4)
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid node id 1")]
+fn insert_edge_to_invalid_node() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    graph.insert_edge(a, 1, Props { cost: Some(1.0) });
+}
+
+#[test]
+fn no_cost() {
+    let (mut graph, ab) = pair(None);
+    assert_eq!(
+        graph.try_best_path(0, &[1]),
+        Err(Error::NoCostOnAdvancedState(ab))
+    );
+}
+
+#[test]
+fn negative_cost() {
+    let (mut graph, ab) = pair(Some(-1.0));
+    assert_eq!(graph.try_best_path(0, &[1]), Err(Error::NegativeCost(ab)));
+}
+
+#[test]
+fn nan_cost() {
+    let (graph, ab) = pair(Some(f64::NAN));
+    assert_eq!(graph.try_search(0, &[1]).unwrap_err(), Error::NaNCost(ab));
+}
+
+#[test]
+fn no_path() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+
+    assert_eq!(graph.try_best_path(a, &[b]), Err(Error::NoPath));
+    assert_eq!(graph.try_search(a, &[b]).unwrap_err(), Error::NoPath);
+}
+
+#[test]
+#[should_panic(expected = "NaN cost on the state advanced along edge 0")]
+fn best_path_panics() {
+    let (mut graph, _) = pair(Some(f64::NAN));
+    graph.best_path(0, &[1]);
+}
+
+fn pair(cost: Option<f64>) -> (Graph<State, Props>, usize) {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let ab = graph.insert_edge(a, b, Props { cost });
+    (graph, ab)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+// the cost of the advanced state is taken from the edge as is
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: Option<f64>,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: edge_props.cost,
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
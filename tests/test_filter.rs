@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::search::SearchOptions;
+
+#[test]
+fn no_filter() {
+    let graph = diamond();
+
+    let result = graph
+        .search_with(0, &[2], &SearchOptions::default())
+        .unwrap();
+
+    assert_eq!(result.edges, graph.search(0, &[2]).unwrap().edges);
+}
+
+#[test]
+fn edge_filter() {
+    let graph = diamond();
+    // avoid the expensive edges
+    let options = SearchOptions {
+        edge_filter: Some(Box::new(|_, props: &Props| props.cost < 15)),
+        ..SearchOptions::default()
+    };
+
+    let result = graph.search_with(0, &[2], &options);
+
+    assert!(result.is_none());
+    // the graph is not changed by the filter
+    assert_eq!(graph.search(0, &[2]).unwrap().cost, 30.0);
+}
+
+#[test]
+fn edge_blocklist() {
+    let graph = diamond();
+    let blocked = [0, 3];
+    let options = SearchOptions {
+        edge_filter: Some(Box::new(|edge_id, _: &Props| !blocked.contains(&edge_id))),
+        ..SearchOptions::default()
+    };
+
+    let result = graph.search_with(0, &[2], &options).unwrap();
+
+    // 0-3-1-2 is the only path left once 0-1 and 3-2 are blocked
+    assert_eq!(result.edges, [2, 4, 1]);
+    assert_eq!(result.cost, 101.0);
+}
+
+#[test]
+fn node_blocklist() {
+    let graph = diamond();
+    let blocked = [3];
+    let options = SearchOptions {
+        node_filter: Some(Box::new(|node_id| !blocked.contains(&node_id))),
+        ..SearchOptions::default()
+    };
+
+    let result = graph.search_with(0, &[2], &options).unwrap();
+
+    assert_eq!(result.edges, [0, 1]);
+    assert_eq!(result.nodes, [0, 1, 2]);
+    assert_eq!(result.cost, 91.0);
+}
+
+#[test]
+fn filtered_edges_not_advanced() {
+    let graph = diamond();
+    let calls = AtomicUsize::new(0);
+    let options = SearchOptions {
+        edge_filter: Some(Box::new(|_, _: &Props| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            false
+        })),
+        ..SearchOptions::default()
+    };
+
+    assert!(graph.search_with(0, &[2], &options).is_none());
+    let result = graph.try_search_with(0, &[0], &options).unwrap();
+
+    assert_eq!(result.stats.advanced, 0);
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
+
+// three paths are possible from 0 to 2: 01-12, 03-31-12, and 03-32
+fn diamond() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+    let d = graph.insert_node(State { cost: None });
+    graph.insert_edge(a, b, Props { cost: 1 });
+    graph.insert_edge(b, c, Props { cost: 90 });
+    graph.insert_edge(a, d, Props { cost: 10 });
+    graph.insert_edge(d, c, Props { cost: 20 });
+    graph.insert_edge(d, b, Props { cost: 1 });
+    graph
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
@@ -0,0 +1,182 @@
+use dijkstra::graph::{Advance, Dominate, EdgeId, Graph, Retreat, TryAdvance};
+use dijkstra::search::SearchOptions;
+
+#[test]
+fn csr_layout() {
+    let frozen = sample().freeze();
+
+    assert_eq!(frozen.num_nodes(), 6);
+    assert_eq!(frozen.num_edges(), 7);
+    assert_eq!(frozen.node_ids().collect::<Vec<_>>(), [0, 1, 2, 3, 5]);
+    // the outgoing edges of each node get consecutive ids
+    assert_eq!(frozen.outgoing(0), 0..2);
+    assert_eq!(frozen.outgoing(2), 3..5);
+    assert_eq!(frozen.outgoing(4), 6..6);
+    for edge_id in frozen.edge_ids() {
+        let from = frozen.edge_from(edge_id);
+        assert!(frozen.outgoing(from).contains(&edge_id));
+    }
+    assert_eq!(frozen.original_edge(4), 6);
+    assert_eq!(frozen.props(4).cost, 1.0);
+}
+
+#[test]
+fn same_as_graph() {
+    let graph = sample();
+    let frozen = sample().freeze();
+
+    for target in 0..4 {
+        let result = graph.search(0, &[target]).unwrap();
+        let frozen_result = frozen.search(0, &[target]).unwrap();
+
+        let original_edges = frozen_result
+            .edges
+            .iter()
+            .map(|&edge_id| frozen.original_edge(edge_id))
+            .collect::<Vec<_>>();
+        assert_eq!(original_edges, result.edges);
+        assert_eq!(frozen_result.nodes, result.nodes);
+        assert_eq!(frozen_result.cost, result.cost);
+    }
+    assert_eq!(
+        frozen.shortest_path_tree(0).costs,
+        graph.shortest_path_tree(0).costs
+    );
+}
+
+#[test]
+fn search_apis() {
+    let frozen = sample().freeze();
+
+    let astar = frozen.search_astar(0, &[3], |_| 0.0.into()).unwrap();
+    let goal = frozen.search_goal(0, |id, _| id == 3).unwrap();
+    let options = SearchOptions {
+        node_filter: Some(Box::new(|id| id != 2)),
+        ..SearchOptions::default()
+    };
+    let detour = frozen.search_with(0, &[3], &options).unwrap();
+
+    assert_eq!(astar.nodes, [0, 2, 1, 3]);
+    assert_eq!(goal.nodes, astar.nodes);
+    assert_eq!(detour.nodes, [0, 1, 3]);
+    assert_eq!(detour.cost, 7.0);
+    assert!(frozen.search(0, &[5]).is_none());
+}
+
+#[test]
+fn incoming() {
+    let graph = sample();
+    let frozen = sample().freeze();
+
+    for id in frozen.node_ids() {
+        let mut original_edges = frozen
+            .incoming(id)
+            .iter()
+            .map(|&edge_id| frozen.original_edge(edge_id))
+            .collect::<Vec<_>>();
+        original_edges.sort();
+        assert_eq!(original_edges, graph.incoming(id));
+        assert!(frozen
+            .incoming(id)
+            .iter()
+            .all(|&edge_id| frozen.edge_to(edge_id) == id));
+    }
+    assert!(frozen.incoming(4).is_empty());
+}
+
+#[test]
+fn other_searches() {
+    let graph = sample();
+    let frozen = sample().freeze();
+    let original = |edges: &[EdgeId]| {
+        edges
+            .iter()
+            .map(|&edge_id| frozen.original_edge(edge_id))
+            .collect::<Vec<_>>()
+    };
+
+    let bidirectional = frozen.search_bidirectional(0, &[3]).unwrap();
+    let k_best = frozen.k_best_paths(0, 3, 3);
+    let pareto = frozen.pareto_paths(0, 3);
+    let constrained = frozen.constrained_path(0, 3).unwrap();
+    let bellman_ford = frozen.search_bellman_ford(0, &[3]).unwrap();
+
+    let expected = graph.search(0, &[3]).unwrap();
+    assert_eq!(original(&bidirectional.edges), expected.edges);
+    assert_eq!(original(&pareto[0].edges), expected.edges);
+    assert_eq!(original(&constrained.edges), expected.edges);
+    assert_eq!(original(&bellman_ford.edges), expected.edges);
+    let graph_k_best = graph.k_best_paths(0, 3, 3);
+    assert_eq!(k_best.len(), graph_k_best.len());
+    for (path, graph_path) in k_best.iter().zip(&graph_k_best) {
+        assert_eq!(original(&path.edges), graph_path.edges);
+        assert_eq!(path.cost, graph_path.cost);
+    }
+    assert_eq!(frozen.all_pairs(), graph.all_pairs());
+}
+
+// 0 -5-> 1 -2-> 3
+// 0 -1-> 2 -1-> 1
+// 2 -6-> 3, 3 -1-> 0, and 4 is removed along with its edges
+fn sample() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..6 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 1, Props { cost: 5.0 });
+    graph.insert_edge(1, 3, Props { cost: 2.0 });
+    graph.insert_edge(0, 2, Props { cost: 1.0 });
+    graph.insert_edge(2, 3, Props { cost: 6.0 });
+    graph.insert_edge(3, 4, Props { cost: 1.0 });
+    graph.insert_edge(3, 0, Props { cost: 1.0 });
+    graph.insert_edge(2, 1, Props { cost: 1.0 });
+    graph.insert_edge(5, 3, Props { cost: 1.0 });
+    graph.remove_node(4);
+    graph
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: f64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = dijkstra::cost::OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<Self::Cost> {
+        self.cost.map(Into::into)
+    }
+}
+
+impl Retreat<State, Props> for State {
+    fn retreat(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost),
+        }
+    }
+}
+
+impl Dominate<State, Props> for State {
+    fn dominates(&self, other: &State) -> bool {
+        self.cost <= other.cost
+    }
+}
+
+// with a limit of 8 on the cost
+impl TryAdvance<State, Props> for State {
+    fn try_advance(&self, edge_props: &Props) -> Option<State> {
+        Some(self.advance(edge_props)).filter(|state| state.cost <= Some(8.0))
+    }
+}
@@ -0,0 +1,109 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn same_as_targets() {
+    let graph = stations();
+
+    let goal = graph.search_goal(0, |id, _| id == 3).unwrap();
+    let targets = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(goal.target, 3);
+    assert_eq!(goal.edges, targets.edges);
+    assert_eq!(goal.cost, targets.cost);
+    assert_eq!(goal.stats, targets.stats);
+}
+
+#[test]
+fn depends_on_state() {
+    let graph = stations();
+
+    // the cheapest place to fill up the tank, whichever node that is
+    let result = graph.search_goal(0, |_, state| state.fuel >= 10).unwrap();
+
+    assert_eq!(result.target, 2);
+    assert_eq!(result.edges, [1]);
+    assert_eq!(result.state.fuel, 12);
+    assert_eq!(result.cost, 4.0);
+}
+
+#[test]
+fn source_is_goal() {
+    let graph = stations();
+
+    let result = graph.search_goal(0, |_, state| state.fuel == 0).unwrap();
+
+    assert_eq!(result.target, 0);
+    assert!(result.edges.is_empty());
+}
+
+#[test]
+fn unreachable() {
+    let graph = stations();
+
+    assert!(graph.search_goal(0, |_, state| state.fuel > 100).is_none());
+    assert_eq!(
+        graph.try_search_goal(9, |_, _| true).unwrap_err(),
+        Error::InvalidNode(9)
+    );
+}
+
+#[test]
+fn many_targets() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..1000 {
+        graph.insert_node(State::default());
+    }
+    for id in 1..1000 {
+        graph.insert_edge(id - 1, id, Props { cost: 1, fuel: 0 });
+    }
+    let targets = (500..1000).rev().collect::<Vec<_>>();
+
+    let result = graph.search(0, &targets).unwrap();
+
+    assert_eq!(result.target, 500);
+    assert_eq!(result.cost, 500.0);
+}
+
+// 0 -1-> 1 (+5 fuel) -1-> 3
+// 0 -4-> 2 (+12 fuel) -1-> 3
+fn stations() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..4 {
+        graph.insert_node(State::default());
+    }
+    graph.insert_edge(0, 1, Props { cost: 1, fuel: 5 });
+    graph.insert_edge(0, 2, Props { cost: 4, fuel: 12 });
+    graph.insert_edge(1, 3, Props { cost: 1, fuel: 0 });
+    graph.insert_edge(2, 3, Props { cost: 1, fuel: 0 });
+    graph
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    cost: Option<f64>,
+    fuel: u8,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: u8,
+    fuel: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+            fuel: self.fuel + edge_props.fuel,
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        *self = node_state;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use dijkstra::cost::OrderedFloat;
 use dijkstra::graph::{Advance, Graph};
 
 #[test]
@@ -158,6 +159,7 @@ struct Props {
 }
 
 impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
     fn advance(&self, edge_props: &Props) -> State {
         State {
             name: self.name,
@@ -167,7 +169,7 @@ impl Advance<State, Props> for State {
     fn update(&mut self, node_state: State) {
         self.cost = node_state.cost;
     }
-    fn cost(&self) -> Option<f64> {
-        self.cost
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
     }
 }
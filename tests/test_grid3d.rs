@@ -1,3 +1,6 @@
+// the test came with the crate, and writes out the field names
+#![allow(clippy::redundant_field_names)]
+
 use dijkstra::advance::{Props, State};
 use dijkstra::graph::{Graph, NodeId};
 
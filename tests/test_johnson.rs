@@ -0,0 +1,120 @@
+use rayon::prelude::*;
+
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn negative_edge() {
+    let graph = shortcut();
+
+    let matrix = graph.all_pairs();
+
+    assert_eq!(matrix.cost(0, 3), Some(6));
+    assert_eq!(matrix.cost(0, 1), Some(2));
+    assert_eq!(matrix.cost(2, 3), Some(2));
+    assert_eq!(matrix.cost(1, 1), Some(0));
+    assert_eq!(matrix.cost(3, 0), None);
+    assert_eq!(matrix.row(2), [None, Some(-2), Some(0), Some(2)]);
+}
+
+#[test]
+fn same_as_bellman_ford() {
+    let mut graph = shortcut();
+    graph.insert_edge(3, 0, Props { cost: 1 });
+    graph.insert_edge(3, 2, Props { cost: -1 });
+    let removed = graph.insert_node(State { cost: None });
+    graph.insert_edge(removed, 0, Props { cost: -5 });
+    graph.remove_node(removed);
+
+    let matrix = graph.all_pairs();
+
+    for from in graph.node_ids() {
+        for to in graph.node_ids() {
+            let bellman_ford = graph
+                .search_bellman_ford(from, &[to])
+                .map(|result| result.cost);
+            assert_eq!(matrix.cost(from, to), bellman_ford, "{} -> {}", from, to);
+        }
+    }
+    assert!(matrix.row(removed).iter().all(Option::is_none));
+}
+
+#[test]
+fn trees() {
+    let graph = shortcut();
+    let matrix = graph.all_pairs();
+
+    let mut trees = graph.all_pairs_trees().collect::<Vec<_>>();
+    trees.sort_by_key(|tree| tree.source);
+
+    assert_eq!(trees.len(), 4);
+    for tree in &trees {
+        assert_eq!(tree.costs, matrix.row(tree.source));
+    }
+    assert_eq!(trees[0].path(3), Some(vec![1, 2, 3]));
+}
+
+#[test]
+fn negative_cycle() {
+    let mut graph = shortcut();
+    // 1 -> 2 -> 1 costs 1 - 2 = -1, but can't be reached from 3
+    graph.insert_edge(1, 2, Props { cost: 1 });
+
+    assert_eq!(
+        graph.try_all_pairs().unwrap_err(),
+        Error::NegativeCycle(vec![2, 4])
+    );
+    assert_eq!(
+        graph.try_all_pairs_trees().err(),
+        Some(Error::NegativeCycle(vec![2, 4]))
+    );
+}
+
+#[test]
+fn empty() {
+    let graph: Graph<State, Props> = Graph::new();
+
+    let matrix = graph.all_pairs();
+
+    assert_eq!(matrix.num_nodes, 0);
+    assert!(matrix.costs.is_empty());
+}
+
+// 0 -3-> 1 -4-> 3
+// 0 -4-> 2 -(-2)-> 1
+fn shortcut() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..4 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 1, Props { cost: 3 });
+    graph.insert_edge(0, 2, Props { cost: 4 });
+    graph.insert_edge(2, 1, Props { cost: -2 });
+    graph.insert_edge(1, 3, Props { cost: 4 });
+    graph
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    cost: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: i64,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = i64;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.cost),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<i64> {
+        self.cost
+    }
+}
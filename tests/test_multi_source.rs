@@ -0,0 +1,149 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn nearest_source() {
+    let graph = depots();
+
+    let result = graph
+        .search_multi_source(
+            &[
+                (0, State { cost: Some(0.0) }),
+                (1, State { cost: Some(0.0) }),
+            ],
+            &[3],
+        )
+        .unwrap();
+
+    assert_eq!(result.source, 1);
+    assert_eq!(result.nodes, [1, 2, 3]);
+    assert_eq!(result.cost, 6.0);
+}
+
+#[test]
+fn initial_states() {
+    let graph = depots();
+
+    // the second depot only opens later, so the first one is closer in time
+    let result = graph
+        .search_multi_source(
+            &[
+                (0, State { cost: Some(0.0) }),
+                (1, State { cost: Some(5.0) }),
+            ],
+            &[3],
+        )
+        .unwrap();
+
+    assert_eq!(result.source, 0);
+    assert_eq!(result.edges, [0, 2]);
+    assert_eq!(result.cost, 7.0);
+}
+
+#[test]
+fn source_is_target() {
+    let graph = depots();
+
+    let result = graph
+        .search_multi_source(
+            &[
+                (0, State { cost: Some(0.0) }),
+                (3, State { cost: Some(1.0) }),
+            ],
+            &[3],
+        )
+        .unwrap();
+
+    assert_eq!(result.source, 3);
+    assert!(result.edges.is_empty());
+    assert_eq!(result.cost, 1.0);
+}
+
+#[test]
+fn duplicate_sources() {
+    let graph = depots();
+
+    let result = graph
+        .search_multi_source(
+            &[
+                (1, State { cost: Some(5.0) }),
+                (1, State { cost: Some(2.0) }),
+            ],
+            &[3],
+        )
+        .unwrap();
+
+    assert_eq!(result.source, 1);
+    assert_eq!(result.cost, 8.0);
+}
+
+#[test]
+fn single_source() {
+    let graph = depots();
+
+    let multi = graph
+        .search_multi_source(&[(0, graph.state(0).clone())], &[3])
+        .unwrap();
+    let single = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(multi.source, single.source);
+    assert_eq!(multi.edges, single.edges);
+    assert_eq!(multi.cost, single.cost);
+}
+
+#[test]
+fn errors() {
+    let graph = depots();
+
+    assert_eq!(
+        graph.try_search_multi_source(&[], &[3]).unwrap_err(),
+        Error::NoPath
+    );
+    assert_eq!(
+        graph
+            .try_search_multi_source(&[(9, State { cost: None })], &[3])
+            .unwrap_err(),
+        Error::InvalidNode(9)
+    );
+}
+
+// depot 0 -4-> 2 -3-> 3
+// depot 1 -3-> 2
+// depot 0 -20-> 3
+fn depots() -> Graph<State, Props> {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..4 {
+        graph.insert_node(State { cost: None });
+    }
+    graph.insert_edge(0, 2, Props { cost: 4 });
+    graph.insert_edge(1, 2, Props { cost: 3 });
+    graph.insert_edge(2, 3, Props { cost: 3 });
+    graph.insert_edge(0, 3, Props { cost: 20 });
+    graph
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
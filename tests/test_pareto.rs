@@ -0,0 +1,244 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use dijkstra::graph::{Advance, Dominate, Graph, NodeId};
+use dijkstra::Error;
+
+#[test]
+fn trade_off() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..4 {
+        graph.insert_node(State::default());
+    }
+    // fast but hungry, slow but frugal, and one that is worse than the fast one in both
+    let fast = graph.insert_edge(0, 1, Props { time: 1, energy: 9 });
+    let slow = graph.insert_edge(0, 2, Props { time: 5, energy: 2 });
+    let worse = graph.insert_edge(
+        0,
+        3,
+        Props {
+            time: 2,
+            energy: 10,
+        },
+    );
+    let fast_on = graph.insert_edge(1, 3, Props { time: 1, energy: 0 });
+    let slow_on = graph.insert_edge(2, 3, Props { time: 1, energy: 1 });
+
+    let paths = graph.pareto_paths(0, 3);
+
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0].edges, [fast, fast_on]);
+    assert_eq!(paths[0].cost, (2, 9));
+    assert_eq!(paths[1].edges, [slow, slow_on]);
+    assert_eq!(paths[1].cost, (6, 3));
+    assert!(paths.iter().all(|path| path.edges != [worse]));
+    assert_eq!(paths[1].nodes, [0, 2, 3]);
+    assert_eq!(paths[1].states[2].as_ref().unwrap().energy, 2);
+    assert!(paths[1].states[1].is_none());
+}
+
+#[test]
+fn tie_settled_first() {
+    // with the time alone as the cost, the dominated label of v costs the same as the dominating one,
+    // and is settled before it, in either order of the edges from the source
+    for reversed in [false, true] {
+        let mut graph: Graph<Timed, Props> = Graph::new();
+        let (s, u, v, t) = (0, 1, 2, 3);
+        for _ in 0..4 {
+            graph.insert_node(Timed::default());
+        }
+        let direct = Props {
+            time: 5,
+            energy: 10,
+        };
+        let via_u = Props { time: 5, energy: 1 };
+        if reversed {
+            graph.insert_edge(s, u, via_u);
+            graph.insert_edge(s, v, direct);
+        } else {
+            graph.insert_edge(s, v, direct);
+            graph.insert_edge(s, u, via_u);
+        }
+        graph.insert_edge(u, v, Props { time: 0, energy: 1 });
+        graph.insert_edge(v, t, Props { time: 0, energy: 1 });
+
+        let paths = graph.pareto_paths(s, t);
+
+        assert_eq!(paths.len(), 1);
+        assert_eq!((paths[0].state.time, paths[0].state.energy), (5, 3));
+        assert_eq!(paths[0].nodes, [s, u, v, t]);
+    }
+}
+
+#[test]
+fn single_criterion() {
+    let mut graph = random_graph(7, 1);
+    for id in graph.edge_ids().collect::<Vec<_>>() {
+        graph.props_mut(id).energy = 0;
+    }
+
+    let paths = graph.pareto_paths(0, 5);
+    let cheapest = simple_path_costs(&graph, 0, 5).into_iter().min().unwrap();
+
+    // with no trade-off, only the cheapest path is left
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].cost, cheapest);
+}
+
+#[test]
+fn brute_force() {
+    for seed in 0..10 {
+        let graph = random_graph(8, seed);
+
+        let paths = graph.pareto_paths(0, 7);
+
+        let mut costs = paths.iter().map(|path| path.cost).collect::<Vec<_>>();
+        let mut expected = pareto_front(&simple_path_costs(&graph, 0, 7));
+        costs.sort();
+        expected.sort();
+        assert!(!expected.is_empty());
+        assert_eq!(costs, expected, "seed {}", seed);
+    }
+}
+
+#[test]
+fn trivial_and_invalid() {
+    let graph = random_graph(4, 0);
+
+    let paths = graph.pareto_paths(2, 2);
+
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].edges.is_empty());
+    assert_eq!(
+        graph.try_pareto_paths(0, 9).unwrap_err(),
+        Error::InvalidNode(9)
+    );
+}
+
+fn random_graph(num_nodes: usize, seed: u64) -> Graph<State, Props> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..num_nodes {
+        graph.insert_node(State::default());
+    }
+    for from in 0..num_nodes {
+        for to in 0..num_nodes {
+            if from != to && rng.gen_bool(0.4) {
+                let props = Props {
+                    time: rng.gen_range(1, 10),
+                    energy: rng.gen_range(1, 10),
+                };
+                graph.insert_edge(from, to, props);
+            }
+        }
+    }
+    graph
+}
+
+// the costs of all the paths that don't visit any node twice
+fn simple_path_costs(graph: &Graph<State, Props>, from: NodeId, to: NodeId) -> Vec<(u32, u32)> {
+    fn visit(
+        graph: &Graph<State, Props>,
+        node: NodeId,
+        to: NodeId,
+        cost: (u32, u32),
+        visited: &mut Vec<bool>,
+        costs: &mut Vec<(u32, u32)>,
+    ) {
+        if node == to {
+            costs.push(cost);
+            return;
+        }
+        visited[node] = true;
+        for &edge_id in &graph.node(node).outgoing {
+            let next = graph.edge(edge_id).to;
+            if !visited[next] {
+                let props = graph.props(edge_id);
+                let next_cost = (cost.0 + props.time, cost.1 + props.energy);
+                visit(graph, next, to, next_cost, visited, costs);
+            }
+        }
+        visited[node] = false;
+    }
+    let mut costs = Vec::new();
+    let mut visited = vec![false; graph.num_nodes()];
+    visit(graph, from, to, (0, 0), &mut visited, &mut costs);
+    costs
+}
+
+fn pareto_front(costs: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut front = costs
+        .iter()
+        .cloned()
+        .filter(|a| {
+            !costs
+                .iter()
+                .any(|b| b.0 <= a.0 && b.1 <= a.1 && (b.0 < a.0 || b.1 < a.1))
+        })
+        .collect::<Vec<_>>();
+    front.sort();
+    front.dedup();
+    front
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    time: u32,
+    energy: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Props {
+    time: u32,
+    energy: u32,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = (u32, u32);
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            time: self.time + edge_props.time,
+            energy: self.energy + edge_props.energy,
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        *self = node_state;
+    }
+    fn cost(&self) -> Option<(u32, u32)> {
+        Some((self.time, self.energy))
+    }
+}
+
+impl Dominate<State, Props> for State {
+    fn dominates(&self, other: &State) -> bool {
+        self.time <= other.time && self.energy <= other.energy
+    }
+}
+
+// the same criteria, but with only the time as the cost
+#[derive(Debug, Clone, Default)]
+struct Timed {
+    time: u32,
+    energy: u32,
+}
+
+impl Advance<Timed, Props> for Timed {
+    type Cost = u32;
+    fn advance(&self, edge_props: &Props) -> Timed {
+        Timed {
+            time: self.time + edge_props.time,
+            energy: self.energy + edge_props.energy,
+        }
+    }
+    fn update(&mut self, node_state: Timed) {
+        *self = node_state;
+    }
+    fn cost(&self) -> Option<u32> {
+        Some(self.time)
+    }
+}
+
+impl Dominate<Timed, Props> for Timed {
+    fn dominates(&self, other: &Timed) -> bool {
+        self.time <= other.time && self.energy <= other.energy
+    }
+}
@@ -1,4 +1,6 @@
-use dijkstra::priority_queue::Heap;
+use dijkstra::priority_queue::{
+    BucketQueue, DaryHeap, Heap, LazyHeap, PairingHeap, PriorityQueue, RadixHeap,
+};
 
 #[test]
 fn test() {
@@ -24,3 +26,178 @@ fn test() {
     assert_eq!(heap.extract_min(), Some((4, 0.9)));
     assert_eq!(heap.extract_min(), None);
 }
+
+#[test]
+fn decrease_key() {
+    let mut heap: Heap<f64> = Heap::new();
+    heap.insert(1, 0.3);
+    heap.insert(2, 0.5);
+    heap.insert(3, 0.7);
+
+    assert_eq!(heap.len(), 3);
+    assert!(heap.contains(3));
+    assert!(!heap.contains(4));
+    assert_eq!(heap.peek(), Some((1, 0.3)));
+
+    assert!(heap.decrease_key(3, 0.1));
+    assert!(!heap.decrease_key(2, 0.6));
+    assert!(!heap.decrease_key(4, 0.1));
+    assert_eq!(heap.peek(), Some((3, 0.1)));
+
+    // inserting an id that is already in the heap only ever lowers its cost, while set_cost also raises it
+    heap.insert(2, 0.6);
+    assert_eq!(heap.peek(), Some((3, 0.1)));
+    heap.set_cost(1, 0.9);
+    assert_eq!(heap.len(), 3);
+
+    assert_eq!(heap.extract_min(), Some((3, 0.1)));
+    assert!(!heap.contains(3));
+    assert_eq!(heap.extract_min(), Some((2, 0.5)));
+    assert_eq!(heap.extract_min(), Some((1, 0.9)));
+    assert_eq!(heap.extract_min(), None);
+}
+
+#[test]
+fn remove() {
+    let mut heap: Heap<f64> = Heap::new();
+    for (id, cost) in [0.3, 0.5, 0.7, 0.9, 0.4, 0.8, 0.6].iter().enumerate() {
+        heap.insert(id, *cost);
+    }
+
+    assert_eq!(heap.remove(4), Some(0.4));
+    assert_eq!(heap.remove(4), None);
+    assert_eq!(heap.remove(0), Some(0.3));
+    assert_eq!(heap.len(), 5);
+
+    let mut ids = Vec::new();
+    while let Some((id, _)) = heap.extract_min() {
+        ids.push(id);
+    }
+    assert_eq!(ids, [1, 6, 2, 5, 3]);
+    assert!(heap.is_empty());
+}
+
+#[test]
+fn backends() {
+    sorts::<Heap<f64>>();
+    sorts::<DaryHeap<f64, 4>>();
+    sorts::<LazyHeap<f64>>();
+    sorts::<PairingHeap<f64>>();
+    sorts::<RadixHeap<f64>>();
+    sorts_integers::<Heap<u32>>();
+    sorts_integers::<DaryHeap<u32, 3>>();
+    sorts_integers::<PairingHeap<u32>>();
+    sorts_integers::<RadixHeap<u32>>();
+    sorts_integers::<BucketQueue<u32>>();
+}
+
+#[test]
+fn monotone() {
+    // the monotone queues accept items inserted after extracting cheaper ones
+    fn interleaved<Queue: PriorityQueue<u32>>() {
+        let mut queue = Queue::new();
+        queue.insert(1, 5);
+        queue.insert(2, 3);
+        assert_eq!(queue.extract_min(), Some((2, 3)));
+        queue.insert(3, 3);
+        queue.insert(4, 9);
+        queue.insert(5, 4);
+        assert_eq!(queue.peek(), Some((3, 3)));
+        assert_eq!(queue.extract_min(), Some((3, 3)));
+        assert_eq!(queue.extract_min(), Some((5, 4)));
+        assert_eq!(queue.extract_min(), Some((1, 5)));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.extract_min(), Some((4, 9)));
+        assert!(queue.is_empty());
+    }
+    interleaved::<RadixHeap<u32>>();
+    interleaved::<BucketQueue<u32>>();
+    interleaved::<PairingHeap<u32>>();
+}
+
+#[test]
+fn pairing_decrease_key() {
+    let mut heap: PairingHeap<f64> = PairingHeap::new();
+    for (id, cost) in [0.3, 0.5, 0.7, 0.9, 0.4, 0.8, 0.6].iter().enumerate() {
+        heap.insert(id, *cost);
+    }
+    assert_eq!(heap.extract_min(), Some((0, 0.3)));
+
+    assert!(heap.decrease_key(3, 0.1));
+    assert!(!heap.decrease_key(2, 0.8));
+    // inserting an id that is already in the heap lowers its cost
+    heap.insert(5, 0.2);
+    assert_eq!(heap.len(), 6);
+
+    let mut ids = Vec::new();
+    while let Some((id, _)) = heap.extract_min() {
+        ids.push(id);
+    }
+    assert_eq!(ids, [3, 5, 4, 1, 6, 2]);
+}
+
+fn sorts<Queue: PriorityQueue<f64>>() {
+    let mut queue = Queue::new();
+    let costs = [0.3, 0.5, 0.7, 0.9, 0.4, 0.8, 0.6, 0.2, 0.1];
+    for (id, cost) in costs.iter().enumerate() {
+        queue.insert(id, *cost);
+    }
+    assert_eq!(queue.len(), costs.len());
+    assert_eq!(queue.peek(), Some((8, 0.1)));
+    let mut extracted = Vec::new();
+    while let Some((_, cost)) = queue.extract_min() {
+        extracted.push(cost);
+    }
+    assert_eq!(extracted, [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]);
+    assert!(queue.is_empty());
+}
+
+fn sorts_integers<Queue: PriorityQueue<u32>>() {
+    let mut queue = Queue::new();
+    let costs = [30, 5, 70, 90, 40, 80, 60, 20, 10, 5];
+    for (id, cost) in costs.iter().enumerate() {
+        queue.insert(id, *cost);
+    }
+    let mut extracted = Vec::new();
+    while let Some((_, cost)) = queue.extract_min() {
+        extracted.push(cost);
+    }
+    assert_eq!(extracted, [5, 5, 10, 20, 30, 40, 60, 70, 80, 90]);
+}
+
+#[test]
+fn insert_keeps_lower() {
+    // through the trait, all the queues put out the lowest cost inserted for an id first
+    fn keeps_lower<Queue: PriorityQueue<u32>>() {
+        let mut queue = Queue::new();
+        queue.insert(1, 3);
+        queue.insert(1, 5);
+        queue.insert(2, 4);
+        queue.insert(3, 6);
+        queue.insert(3, 2);
+        assert_eq!(queue.extract_min(), Some((3, 2)));
+        assert_eq!(queue.extract_min(), Some((1, 3)));
+        assert_eq!(queue.extract_min(), Some((2, 4)));
+    }
+    keeps_lower::<Heap<u32>>();
+    keeps_lower::<DaryHeap<u32, 4>>();
+    keeps_lower::<LazyHeap<u32>>();
+    keeps_lower::<PairingHeap<u32>>();
+    keeps_lower::<RadixHeap<u32>>();
+    keeps_lower::<BucketQueue<u32>>();
+}
+
+#[test]
+fn lazy() {
+    let mut heap: LazyHeap<f64> = LazyHeap::new();
+    heap.insert(1, 0.5);
+    heap.insert(2, 0.3);
+    heap.insert(1, 0.1);
+
+    assert_eq!(heap.len(), 3);
+    assert_eq!(heap.peek(), Some((1, 0.1)));
+    assert_eq!(heap.extract_min(), Some((1, 0.1)));
+    assert_eq!(heap.extract_min(), Some((2, 0.3)));
+    assert_eq!(heap.extract_min(), Some((1, 0.5)));
+    assert_eq!(heap.extract_min(), None);
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::Error;
+
+#[test]
+fn remove_edge() {
+    let (mut graph, [a, b, c, d], [ab, bc, ad, dc, db]) = diamond();
+
+    graph.remove_edge(dc);
+
+    assert!(!graph.contains_edge(dc));
+    assert!(graph.edge(dc).removed);
+    assert_eq!(graph.node(d).outgoing, [db]);
+    assert_eq!(graph.incoming(c), [bc]);
+    assert_eq!(graph.edge_ids().collect::<Vec<_>>(), [ab, bc, ad, db]);
+    assert_eq!(graph.num_edges(), 5);
+    assert_eq!(graph.try_remove_edge(dc), Err(Error::InvalidEdge(dc)));
+
+    // the ids of the other edges are still valid
+    assert_eq!(graph.search(a, &[c]).unwrap().edges, [ab, bc]);
+    assert_eq!(graph.edge(db).from, d);
+    assert_eq!(graph.edge(db).to, b);
+}
+
+#[test]
+fn remove_node() {
+    let (mut graph, [a, b, c, d], [_, _, ad, dc, _]) = diamond();
+
+    graph.remove_node(b);
+
+    assert!(!graph.contains_node(b));
+    assert_eq!(graph.node_ids().collect::<Vec<_>>(), [a, c, d]);
+    assert_eq!(graph.edge_ids().collect::<Vec<_>>(), [ad, dc]);
+    assert_eq!(graph.node(a).outgoing, [ad]);
+    assert_eq!(graph.node(d).outgoing, [dc]);
+    assert_eq!(graph.incoming(c), [dc]);
+    assert_eq!(graph.search(a, &[c]).unwrap().edges, [ad, dc]);
+
+    assert_eq!(
+        graph.try_search(a, &[b]).unwrap_err(),
+        Error::InvalidNode(b)
+    );
+    assert_eq!(graph.try_remove_node(b), Err(Error::InvalidNode(b)));
+    assert_eq!(
+        graph.try_insert_edge(a, b, Props { cost: 1 }),
+        Err(Error::InvalidNode(b))
+    );
+}
+
+#[test]
+fn compact() {
+    let (mut graph, [a, b, c, d], [_, _, ad, dc, _]) = diamond();
+    graph.remove_node(b);
+
+    let remap = graph.compact();
+
+    assert_eq!(remap.nodes, [Some(0), None, Some(1), Some(2)]);
+    assert_eq!(remap.edges, [None, None, Some(0), Some(1), None]);
+    assert_eq!(graph.num_nodes(), 3);
+    assert_eq!(graph.num_edges(), 2);
+    let (a, c, d) = (
+        remap.nodes[a].unwrap(),
+        remap.nodes[c].unwrap(),
+        remap.nodes[d].unwrap(),
+    );
+    let (ad, dc) = (remap.edges[ad].unwrap(), remap.edges[dc].unwrap());
+    assert_eq!(graph.state(d).name, 'd');
+    assert_eq!(graph.props(dc).cost, 20);
+    assert_eq!(graph.node(a).outgoing, [ad]);
+    assert_eq!(graph.incoming(c), [dc]);
+    assert_eq!(graph.edge(dc).from, d);
+    assert_eq!(graph.search(a, &[c]).unwrap().edges, [ad, dc]);
+}
+
+#[test]
+fn serde() {
+    let (mut graph, [a, b, c, _], [_, bc, ..]) = diamond();
+    graph.remove_edge(bc);
+
+    let json = serde_json::to_string(&graph).expect("failed to serialise graph");
+    let graph: Graph<State, Props> =
+        serde_json::from_str(&json).expect("failed to deserialise graph");
+
+    assert!(!graph.contains_edge(bc));
+    assert!(graph.node(b).outgoing.is_empty());
+    assert_eq!(graph.incoming(c).len(), 1);
+    assert_eq!(graph.search(a, &[c]).unwrap().cost, 30.0);
+}
+
+// three paths are possible from a to c: ab-bc, ad-db-bc, and ad-dc
+fn diamond() -> (Graph<State, Props>, [usize; 4], [usize; 5]) {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State {
+        name: 'a',
+        cost: None,
+    });
+    let b = graph.insert_node(State {
+        name: 'b',
+        cost: None,
+    });
+    let c = graph.insert_node(State {
+        name: 'c',
+        cost: None,
+    });
+    let d = graph.insert_node(State {
+        name: 'd',
+        cost: None,
+    });
+    let ab = graph.insert_edge(a, b, Props { cost: 1 });
+    let bc = graph.insert_edge(b, c, Props { cost: 90 });
+    let ad = graph.insert_edge(a, d, Props { cost: 10 });
+    let dc = graph.insert_edge(d, c, Props { cost: 20 });
+    let db = graph.insert_edge(d, b, Props { cost: 1 });
+    (graph, [a, b, c, d], [ab, bc, ad, dc, db])
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct State {
+    name: char,
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            name: self.name,
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::priority_queue::{BucketQueue, DaryHeap, LazyHeap, PairingHeap, RadixHeap};
+use dijkstra::search::SearchOptions;
+
+#[test]
+fn leaves_states_untouched() {
+    let graph = diamond();
+
+    let result = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(result.edges, [2, 3]);
+    assert_eq!(result.nodes, [0, 2, 3]);
+    assert_eq!(result.target, 3);
+    assert_eq!(result.cost, 30.0);
+    assert_eq!(result.state.cost, Some(30.0));
+    assert_eq!(result.states[3].as_ref().unwrap().cost, Some(30.0));
+    assert_eq!(result.states[1].as_ref().unwrap().cost, Some(1.0));
+    assert!(result.states[0].is_none());
+    for id in 0..graph.num_nodes() {
+        assert_eq!(graph.state(id).cost, None);
+    }
+}
+
+#[test]
+fn repeated() {
+    let graph = diamond();
+
+    let first = graph.search(0, &[3]).unwrap();
+    let second = graph.search(1, &[3]).unwrap();
+    let third = graph.search(0, &[3]).unwrap();
+
+    assert_eq!(first.edges, [2, 3]);
+    assert_eq!(second.edges, [1]);
+    assert_eq!(second.states[3].as_ref().unwrap().cost, Some(90.0));
+    assert_eq!(third.edges, first.edges);
+}
+
+#[test]
+fn stored_costs() {
+    // as in the README: set the cost of the source and search, then the same from another source,
+    // without resetting the cost stored for the first one
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    graph.insert_edge(a, b, Props { cost: 1 });
+    graph.insert_edge(b, a, Props { cost: 1 });
+
+    graph.state_mut(a).cost = Some(0.0);
+    let first = graph.search(a, &[b]).unwrap();
+    graph.state_mut(b).cost = Some(0.0);
+    let second = graph.search(b, &[a]).unwrap();
+    let tree = graph.shortest_path_tree(b);
+
+    assert_eq!(first.cost, 1.0);
+    assert_eq!(second.cost, 1.0);
+    assert_eq!(
+        tree.costs,
+        [Some(OrderedFloat(1.0)), Some(OrderedFloat(0.0))]
+    );
+    // nor do the costs written back by best_path, except for the source, which starts from its stored state
+    let mut graph = diamond();
+    graph.state_mut(0).cost = Some(0.0);
+    graph.best_path(0, &[3]).unwrap();
+    assert_eq!(graph.state(3).cost, Some(30.0));
+    assert_eq!(graph.search(1, &[3]).unwrap().cost, 1.0 + 90.0);
+}
+
+#[test]
+fn concurrent() {
+    let graph = diamond();
+
+    let paths = std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| scope.spawn(|| graph.search(0, &[3]).unwrap().edges))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    for path in paths {
+        assert_eq!(path, [2, 3]);
+    }
+}
+
+#[test]
+fn source_is_target() {
+    let graph = diamond();
+
+    let result = graph.search(2, &[0, 2]).unwrap();
+
+    assert_eq!(result.target, 2);
+    assert_eq!(result.nodes, [2]);
+    assert!(result.edges.is_empty());
+    assert_eq!(result.cost, 0.0);
+    assert_eq!(result.stats.settled, 0);
+}
+
+#[test]
+fn stats() {
+    let graph = diamond();
+
+    let result = graph.search(0, &[3]).unwrap();
+
+    // 0, 1 and 2 are settled before 3 is reached, and 2-1 is not advanced since 1 is already settled
+    assert_eq!(result.stats.settled, 3);
+    assert_eq!(result.stats.advanced, 4);
+    assert_eq!(result.stats.stale, 0);
+}
+
+#[test]
+fn lazy_deletion() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+    let d = graph.insert_node(State { cost: None });
+    graph.insert_edge(a, b, Props { cost: 10 });
+    graph.insert_edge(a, c, Props { cost: 1 });
+    graph.insert_edge(c, b, Props { cost: 1 });
+    graph.insert_edge(b, d, Props { cost: 20 });
+    let options = SearchOptions::default();
+
+    let indexed = graph.search(a, &[d]).unwrap();
+    let lazy = graph
+        .try_search_with_queue::<LazyHeap<OrderedFloat>>(a, &[d], &options)
+        .unwrap();
+
+    // b is queued with cost 10 before it is reached with cost 2 via c,
+    // and the old item comes to the front of the queue before d is reached with cost 22
+    assert_eq!(indexed.edges, lazy.edges);
+    assert_eq!(indexed.cost, 22.0);
+    assert_eq!(indexed.stats.stale, 0);
+    assert_eq!(lazy.stats.stale, 1);
+    assert_eq!(indexed.stats.settled, lazy.stats.settled);
+}
+
+#[test]
+fn queues() {
+    let graph = diamond();
+    let options = SearchOptions::default();
+
+    let results = vec![
+        graph.try_search_with_queue::<DaryHeap<OrderedFloat, 4>>(0, &[3], &options),
+        graph.try_search_with_queue::<LazyHeap<OrderedFloat>>(0, &[3], &options),
+        graph.try_search_with_queue::<PairingHeap<OrderedFloat>>(0, &[3], &options),
+        graph.try_search_with_queue::<RadixHeap<OrderedFloat>>(0, &[3], &options),
+    ];
+
+    for result in results {
+        let result = result.unwrap();
+        assert_eq!(result.edges, [2, 3]);
+        assert_eq!(result.cost, 30.0);
+    }
+    // the bucket queue allocates a bucket for each key up to the highest one, so it needs integer costs
+    let graph = diamond_of(|| Distance { cost: None });
+    let result = graph
+        .try_search_with_queue::<BucketQueue<u32>>(0, &[3], &SearchOptions::default())
+        .unwrap();
+    assert_eq!(result.edges, [2, 3]);
+    assert_eq!(result.cost, 30);
+}
+
+#[test]
+fn disconnected() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+
+    assert!(graph.search(a, &[b]).is_none());
+}
+
+// three paths are possible from 0 to 3: 01-13, 02-21-13, and 02-23
+fn diamond() -> Graph<State, Props> {
+    diamond_of(|| State { cost: None })
+}
+
+fn diamond_of<NodeState: Sync + Send + Advance<NodeState, Props>>(
+    new_state: impl Fn() -> NodeState,
+) -> Graph<NodeState, Props> {
+    let mut graph: Graph<NodeState, Props> = Graph::new();
+    let a = graph.insert_node(new_state());
+    let b = graph.insert_node(new_state());
+    let c = graph.insert_node(new_state());
+    let d = graph.insert_node(new_state());
+    graph.insert_edge(a, b, Props { cost: 1 });
+    graph.insert_edge(b, d, Props { cost: 90 });
+    graph.insert_edge(a, c, Props { cost: 10 });
+    graph.insert_edge(c, d, Props { cost: 20 });
+    graph.insert_edge(c, b, Props { cost: 1 });
+    graph
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Distance {
+    cost: Option<u32>,
+}
+
+impl Advance<Distance, Props> for Distance {
+    type Cost = u32;
+    fn advance(&self, edge_props: &Props) -> Distance {
+        Distance {
+            cost: Some(self.cost.unwrap_or(0) + edge_props.cost as u32),
+        }
+    }
+    fn update(&mut self, node_state: Distance) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<u32> {
+        self.cost
+    }
+}
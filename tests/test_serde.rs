@@ -1,5 +1,9 @@
+// the test came with the crate, and writes out the field names and the casts
+#![allow(clippy::redundant_field_names, clippy::unnecessary_cast)]
+
 use serde::{Deserialize, Serialize};
 
+use dijkstra::cost::OrderedFloat;
 use dijkstra::graph::{Advance, Graph};
 
 #[test]
@@ -17,7 +21,7 @@ fn read_and_search() {
     assert_eq!(graph.edge(path[3]).to, graph.edge(path[4]).from);
     assert_eq!(graph.edge(path[4]).to, 25);
     let best_target = graph.state(25);
-    assert_eq!(best_target.cost(), Some(1.6849905966872787));
+    assert_eq!(best_target.cost(), Some(OrderedFloat(1.6849905966872787)));
 }
 
 #[test]
@@ -53,6 +57,7 @@ pub struct Props {
 }
 
 impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
     fn advance(&self, edge_props: &Props) -> State {
         State {
             name: self.name,
@@ -62,7 +67,7 @@ impl Advance<State, Props> for State {
     fn update(&mut self, node_state: State) {
         self.cost = node_state.cost;
     }
-    fn cost(&self) -> Option<f64> {
-        self.cost
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
     }
 }
@@ -0,0 +1,262 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+use dijkstra::time_dependent::TimeDependentProps;
+use dijkstra::Error;
+
+#[test]
+fn travel_time() {
+    let props = highway();
+
+    assert_eq!(props.travel_time(-5.0), 10.0);
+    assert_eq!(props.travel_time(50.0), 10.0);
+    assert_eq!(props.travel_time(95.0), 20.0);
+    assert_eq!(props.travel_time(150.0), 30.0);
+    assert_eq!(props.travel_time(215.0), 20.0);
+    assert_eq!(props.travel_time(1000.0), 10.0);
+    assert_eq!(props.arrival(95.0), 115.0);
+    assert!(props.is_fifo());
+    assert!(!TimeDependentProps::new(vec![(0.0, 100.0), (10.0, 0.0)]).is_fifo());
+}
+
+#[test]
+fn latest_departure_is_inverse() {
+    let props = highway();
+
+    for departure in (-20..300).map(|time| time as f64) {
+        let arrival = props.arrival(departure);
+        assert!((props.latest_departure(arrival) - departure).abs() < 1e-9);
+    }
+    // while the travel time rises, the arrival time rises faster than the departure time
+    assert!((props.latest_departure(120.0) - (96.0 + 2.0 / 3.0)).abs() < 1e-9);
+}
+
+#[test]
+fn earliest_arrival() {
+    let graph = roads();
+
+    let before = graph.earliest_arrival(0, 0.0, &[1]).unwrap();
+    let rush_hour = graph.earliest_arrival(0, 100.0, &[1]).unwrap();
+
+    assert_eq!(before.edges, [0]);
+    assert_eq!(before.arrival, 10.0);
+    assert_eq!(rush_hour.edges, [1, 2]);
+    assert_eq!(rush_hour.nodes, [0, 2, 1]);
+    assert_eq!(rush_hour.departure, 100.0);
+    assert_eq!(rush_hour.arrival, 120.0);
+}
+
+#[test]
+fn agrees_with_search() {
+    let mut graph = roads();
+
+    for departure in (0..30).map(|step| step as f64 * 10.0) {
+        graph.state_mut(0).time = Some(departure);
+
+        let earliest = graph.earliest_arrival(0, departure, &[1]).unwrap();
+        let search = graph.search(0, &[1]).unwrap();
+
+        assert_eq!(earliest.edges, search.edges);
+        assert_eq!(search.cost, earliest.arrival);
+    }
+}
+
+#[test]
+fn latest_departure() {
+    let graph = roads();
+
+    let in_rush_hour = graph.latest_departure(0, 1, 130.0).unwrap();
+    let before = graph.latest_departure(0, 1, 100.0).unwrap();
+
+    assert_eq!(in_rush_hour.edges, [1, 2]);
+    assert_eq!(in_rush_hour.departure, 110.0);
+    assert_eq!(before.edges, [0]);
+    assert_eq!(before.departure, 90.0);
+    assert_eq!(
+        graph.earliest_arrival(0, 90.0, &[1]).unwrap().arrival,
+        100.0
+    );
+}
+
+#[test]
+fn latest_departures() {
+    let mut graph = roads();
+    let unreachable = graph.insert_node(State { time: None });
+
+    let departures = graph.latest_departures(1, 130.0);
+
+    assert_eq!(departures.departure(1), Some(130.0));
+    assert_eq!(departures.departure(2), Some(122.0));
+    assert_eq!(departures.departure(0), Some(110.0));
+    assert_eq!(departures.departure(unreachable), None);
+    assert_eq!(departures.path(&graph, 0), Some(vec![1, 2]));
+    assert_eq!(departures.path(&graph, 1), Some(vec![]));
+    assert_eq!(departures.path(&graph, unreachable), None);
+}
+
+#[test]
+fn departure_profiles() {
+    let mut graph = roads();
+    let unreachable = graph.insert_node(State { time: None });
+
+    let profiles = graph.departure_profiles(1);
+
+    // the highway until it gets slower than the other road, from 95 to 215, and the highway again after that
+    assert_eq!(
+        profiles.profile(0).unwrap().points,
+        [(90.0, 10.0), (95.0, 20.0), (215.0, 20.0), (230.0, 10.0)]
+    );
+    assert_eq!(profiles.latest_departure(0, 130.0), Some(110.0));
+    assert_eq!(profiles.latest_departure(0, 100.0), Some(90.0));
+    assert_eq!(profiles.earliest_arrival(2, 50.0), Some(58.0));
+    assert_eq!(profiles.profile(unreachable), None);
+    assert_eq!(
+        graph.try_departure_profiles(9).unwrap_err(),
+        Error::InvalidNode(9)
+    );
+}
+
+#[test]
+fn departure_profiles_agree_with_queries() {
+    for seed in 0..10 {
+        let graph = random_roads(8, seed);
+
+        let profiles = graph.departure_profiles(7);
+
+        for arrival in (0..40).map(|step| step as f64 * 10.0) {
+            let departures = graph.latest_departures(7, arrival);
+            for id in graph.node_ids() {
+                let expected = departures.departure(id);
+                let departure = profiles.latest_departure(id, arrival);
+                assert_eq!(departure.is_some(), expected.is_some(), "seed {}", seed);
+                if let (Some(departure), Some(expected)) = (departure, expected) {
+                    assert!((departure - expected).abs() < 1e-6, "seed {}", seed);
+                }
+            }
+        }
+        for departure in (0..40).map(|step| step as f64 * 10.0) {
+            for id in graph.node_ids() {
+                let expected = graph
+                    .earliest_arrival(id, departure, &[7])
+                    .map(|path| path.arrival);
+                let arrival = profiles.earliest_arrival(id, departure);
+                assert_eq!(arrival.is_some(), expected.is_some(), "seed {}", seed);
+                if let (Some(arrival), Some(expected)) = (arrival, expected) {
+                    assert!((arrival - expected).abs() < 1e-6, "seed {}", seed);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn frozen() {
+    // the edges of roads are already in the order of the nodes they start from, so they keep their ids
+    let graph = roads();
+    let frozen = roads().freeze();
+
+    assert_eq!(
+        frozen.earliest_arrival(0, 100.0, &[1]),
+        graph.earliest_arrival(0, 100.0, &[1])
+    );
+    assert_eq!(
+        frozen.latest_departure(0, 1, 130.0),
+        graph.latest_departure(0, 1, 130.0)
+    );
+    assert_eq!(
+        frozen.latest_departures(1, 100.0),
+        graph.latest_departures(1, 100.0)
+    );
+    assert_eq!(frozen.departure_profiles(1), graph.departure_profiles(1));
+    assert_eq!(frozen.check_fifo(), Ok(()));
+}
+
+#[test]
+fn not_fifo() {
+    let mut graph = roads();
+    assert_eq!(graph.check_fifo(), Ok(()));
+    let overtaking = graph.insert_edge(
+        2,
+        1,
+        TimeDependentProps::new(vec![(0.0, 100.0), (10.0, 0.0)]),
+    );
+
+    assert_eq!(graph.check_fifo(), Err(Error::NotFifo(overtaking)));
+    assert_eq!(
+        graph.try_earliest_arrival(0, 100.0, &[1]).unwrap_err(),
+        Error::NotFifo(overtaking)
+    );
+    assert_eq!(
+        graph.try_latest_departures(1, 0.0).unwrap_err(),
+        Error::NotFifo(overtaking)
+    );
+}
+
+// the travel time goes up from 10 to 30 in the rush hour, and back down after it
+fn highway() -> TimeDependentProps {
+    TimeDependentProps::new(vec![
+        (0.0, 10.0),
+        (90.0, 10.0),
+        (100.0, 30.0),
+        (200.0, 30.0),
+        (230.0, 10.0),
+    ])
+}
+
+// 0 -highway-> 1
+// 0 -12-> 2 -8-> 1
+fn roads() -> Graph<State, TimeDependentProps> {
+    let mut graph: Graph<State, TimeDependentProps> = Graph::new();
+    for _ in 0..3 {
+        graph.insert_node(State { time: None });
+    }
+    graph.insert_edge(0, 1, highway());
+    graph.insert_edge(0, 2, TimeDependentProps::constant(12.0));
+    graph.insert_edge(2, 1, TimeDependentProps::constant(8.0));
+    graph
+}
+
+// random FIFO travel times, each rising and falling no faster than the departure time
+fn random_roads(num_nodes: usize, seed: u64) -> Graph<State, TimeDependentProps> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph: Graph<State, TimeDependentProps> = Graph::new();
+    for _ in 0..num_nodes {
+        graph.insert_node(State { time: None });
+    }
+    for from in 0..num_nodes {
+        for to in 0..num_nodes {
+            if from != to && rng.gen_bool(0.3) {
+                let mut points = Vec::new();
+                let mut travel_time: f64 = rng.gen_range(5.0, 50.0);
+                for departure in (0..rng.gen_range(1, 6)).map(|step| step as f64 * 60.0) {
+                    points.push((departure, travel_time));
+                    travel_time = (travel_time + rng.gen_range(-50.0, 50.0)).max(1.0);
+                }
+                graph.insert_edge(from, to, TimeDependentProps::new(points));
+            }
+        }
+    }
+    graph
+}
+
+// the time at which a node is reached
+#[derive(Debug, Clone)]
+struct State {
+    time: Option<f64>,
+}
+
+impl Advance<State, TimeDependentProps> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &TimeDependentProps) -> State {
+        State {
+            time: Some(edge_props.arrival(self.time.unwrap_or(0.0))),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.time = node_state.time;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.time.map(OrderedFloat)
+    }
+}
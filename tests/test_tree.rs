@@ -0,0 +1,102 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+
+#[test]
+fn all_nodes() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    let c = graph.insert_node(State { cost: None });
+    let d = graph.insert_node(State { cost: None });
+    let e = graph.insert_node(State { cost: None });
+
+    let ab = graph.insert_edge(a, b, Props { cost: 1 });
+    graph.insert_edge(b, c, Props { cost: 90 });
+    let ad = graph.insert_edge(a, d, Props { cost: 10 });
+    let dc = graph.insert_edge(d, c, Props { cost: 20 });
+    graph.insert_edge(d, b, Props { cost: 1 });
+    graph.insert_edge(e, a, Props { cost: 1 });
+
+    let tree = graph.shortest_path_tree(a);
+
+    assert_eq!(tree.source, a);
+    assert_eq!(tree.incoming, [None, Some(ab), Some(dc), Some(ad), None]);
+    assert_eq!(tree.parents, [None, Some(a), Some(d), Some(a), None]);
+    assert_eq!(
+        tree.costs,
+        [
+            Some(OrderedFloat(0.0)),
+            Some(OrderedFloat(1.0)),
+            Some(OrderedFloat(30.0)),
+            Some(OrderedFloat(10.0)),
+            None
+        ]
+    );
+
+    assert_eq!(tree.path(a), Some(vec![]));
+    assert_eq!(tree.path(b), Some(vec![ab]));
+    assert_eq!(tree.path(c), Some(vec![ad, dc]));
+    assert_eq!(tree.path(e), None);
+    assert_eq!(tree.cost(c), Some(OrderedFloat(30.0)));
+    assert_eq!(tree.cost(e), None);
+
+    // the graph is not modified by the search
+    assert_eq!(graph.state(c).cost, None);
+}
+
+#[test]
+fn agrees_with_search() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    for _ in 0..26 {
+        graph.insert_node(State { cost: None });
+    }
+    for i in 0..100 {
+        // deterministic pseudo-random edges
+        let from = (i * 7) % 26;
+        let to = (i * 11 + 3) % 26;
+        graph.insert_edge(
+            from,
+            to,
+            Props {
+                cost: ((i * 13) % 17) as u8,
+            },
+        );
+    }
+
+    let tree = graph.shortest_path_tree(0);
+
+    for id in 0..26 {
+        match graph.search(0, &[id]) {
+            Some(result) => {
+                assert_eq!(tree.cost(id), Some(result.cost));
+                assert_eq!(tree.path(id).unwrap().len(), result.edges.len());
+            }
+            None => assert_eq!(tree.path(id), None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}
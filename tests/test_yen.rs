@@ -0,0 +1,98 @@
+use dijkstra::cost::OrderedFloat;
+use dijkstra::graph::{Advance, Graph};
+
+#[test]
+fn ranked() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let c = graph.insert_node(State { cost: None });
+    let d = graph.insert_node(State { cost: None });
+    let e = graph.insert_node(State { cost: None });
+    let f = graph.insert_node(State { cost: None });
+    let g = graph.insert_node(State { cost: None });
+    let h = graph.insert_node(State { cost: None });
+
+    // the example from the wikipedia article on Yen's algorithm
+    let cd = graph.insert_edge(c, d, Props { cost: 3 });
+    let ce = graph.insert_edge(c, e, Props { cost: 2 });
+    let df = graph.insert_edge(d, f, Props { cost: 4 });
+    let ed = graph.insert_edge(e, d, Props { cost: 1 });
+    let ef = graph.insert_edge(e, f, Props { cost: 2 });
+    let eg = graph.insert_edge(e, g, Props { cost: 3 });
+    let fg = graph.insert_edge(f, g, Props { cost: 2 });
+    let fh = graph.insert_edge(f, h, Props { cost: 1 });
+    let gh = graph.insert_edge(g, h, Props { cost: 2 });
+
+    let paths = graph.k_best_paths(c, h, 3);
+
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[0].edges, [ce, ef, fh]);
+    assert_eq!(paths[0].cost, 5.0);
+    assert_eq!(paths[1].edges, [ce, eg, gh]);
+    assert_eq!(paths[1].cost, 7.0);
+    assert_eq!(paths[2].edges, [cd, df, fh]);
+    assert_eq!(paths[2].nodes, [c, d, f, h]);
+    assert_eq!(paths[2].cost, 8.0);
+    assert_eq!(paths[2].state.cost, Some(8.0));
+
+    // all the loopless paths
+    let paths = graph.k_best_paths(c, h, 100);
+    let costs = paths.iter().map(|path| path.cost).collect::<Vec<_>>();
+    assert_eq!(costs, [5.0, 7.0, 8.0, 8.0, 8.0, 11.0, 11.0]);
+    assert!(paths.iter().any(|path| path.edges == [ce, ed, df, fh]));
+    assert!(paths.iter().any(|path| path.edges == [cd, df, fg, gh]));
+}
+
+#[test]
+fn multi_edge() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+
+    let u = graph.insert_edge(a, b, Props { cost: 3 });
+    let v = graph.insert_edge(a, b, Props { cost: 2 });
+    let w = graph.insert_edge(a, b, Props { cost: 1 });
+
+    let paths = graph.k_best_paths(a, b, 5);
+
+    let edges = paths
+        .iter()
+        .map(|path| path.edges.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(edges, [[w], [v], [u]]);
+}
+
+#[test]
+fn no_path() {
+    let mut graph: Graph<State, Props> = Graph::new();
+    let a = graph.insert_node(State { cost: None });
+    let b = graph.insert_node(State { cost: None });
+    graph.insert_edge(b, a, Props { cost: 1 });
+
+    assert!(graph.k_best_paths(a, b, 3).is_empty());
+    assert!(graph.k_best_paths(b, a, 0).is_empty());
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Props {
+    cost: u8,
+}
+
+impl Advance<State, Props> for State {
+    type Cost = OrderedFloat;
+    fn advance(&self, edge_props: &Props) -> State {
+        State {
+            cost: Some(self.cost.unwrap_or(0.0) + edge_props.cost as f64),
+        }
+    }
+    fn update(&mut self, node_state: State) {
+        self.cost = node_state.cost;
+    }
+    fn cost(&self) -> Option<OrderedFloat> {
+        self.cost.map(OrderedFloat)
+    }
+}